@@ -0,0 +1,35 @@
+//! Records the let-engine version, so benchmark reports can tell engine builds apart.
+
+use std::{env, fs, path::Path};
+
+fn main() {
+    let manifest = Path::new(&env::var("CARGO_MANIFEST_DIR").unwrap_or_default())
+        .join("../let-engine/let-engine/Cargo.toml");
+    println!("cargo:rerun-if-changed={}", manifest.display());
+    let version = fs::read_to_string(&manifest)
+        .ok()
+        .and_then(|contents| package_version(&contents))
+        .unwrap_or_else(|| "unknown".to_string());
+    println!("cargo:rustc-env=LET_ENGINE_VERSION={version}");
+}
+
+/// The `version` key of the `[package]` table.
+fn package_version(manifest: &str) -> Option<String> {
+    let mut in_package = false;
+    for line in manifest.lines().map(str::trim) {
+        if line.starts_with('[') {
+            in_package = line == "[package]";
+            continue;
+        }
+        if !in_package {
+            continue;
+        }
+        if let Some(value) = line
+            .strip_prefix("version")
+            .and_then(|rest| rest.trim_start().strip_prefix('='))
+        {
+            return Some(value.trim().trim_matches('"').to_string());
+        }
+    }
+    None
+}
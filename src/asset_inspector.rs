@@ -0,0 +1,405 @@
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
+
+use egui::{Color32, RichText};
+use serde::Deserialize;
+
+use crate::assets_dir;
+
+/// Files the sandbox reads straight from the assets directory. They are never packed.
+const SANDBOX_FILES: [&str; 4] = [
+    "bindings.toml",
+    "material_presets.toml",
+    "physics_presets.toml",
+    "scripts",
+];
+
+/// The packer's `config.toml` in the assets directory.
+#[derive(Deserialize)]
+struct PackConfig {
+    max_size: Option<u64>,
+    naming: Option<String>,
+    output: Option<String>,
+    split_by_group: Option<bool>,
+    #[serde(default)]
+    exclude: Vec<String>,
+    #[serde(default)]
+    groups: BTreeMap<String, String>,
+}
+
+/// A group's own `config.toml`.
+#[derive(Deserialize, Default)]
+struct GroupConfig {
+    compression: Option<String>,
+    compression_level: Option<i32>,
+}
+
+struct FileInfo {
+    /// Path relative to the assets directory, as passed to `asset`.
+    path: String,
+    raw_size: u64,
+    excluded: bool,
+    /// Unpacked size and load time, or why loading failed.
+    load: Result<(usize, Duration), String>,
+}
+
+struct GroupInfo {
+    name: String,
+    dir: String,
+    codec: String,
+    files: Vec<FileInfo>,
+    /// Pack files of this group with their sizes.
+    packs: Vec<(String, u64)>,
+}
+
+impl GroupInfo {
+    fn raw_size(&self) -> u64 {
+        self.files
+            .iter()
+            .filter(|file| !file.excluded)
+            .map(|file| file.raw_size)
+            .sum()
+    }
+
+    fn packed_size(&self) -> u64 {
+        self.packs.iter().map(|(_, size)| size).sum()
+    }
+}
+
+struct Scan {
+    config: PackConfig,
+    packs_dir: PathBuf,
+    groups: Vec<GroupInfo>,
+    warnings: Vec<String>,
+}
+
+/// Shows how the asset system packed the files of the assets directory.
+///
+/// The pack format does not expose per-file compressed sizes, so ratios are per group:
+/// the size of the group's pack files against the raw size of its files. The packed
+/// size shown per file is that ratio applied to the file, and files of a split group
+/// cannot be told apart by pack.
+pub struct AssetInspector {
+    scan: Option<Result<Scan, String>>,
+    /// Paths the sandbox loads, checked on every scan.
+    requested: Vec<String>,
+    probe: String,
+}
+
+impl AssetInspector {
+    pub fn new(requested: &[&str]) -> Self {
+        Self {
+            scan: None,
+            requested: requested.iter().map(|path| path.to_string()).collect(),
+            probe: String::new(),
+        }
+    }
+
+    fn scan(&self) -> Result<Scan, String> {
+        let root = assets_dir();
+        let config: PackConfig = toml::from_str(
+            &fs::read_to_string(root.join("config.toml")).map_err(|e| e.to_string())?,
+        )
+        .map_err(|e| e.to_string())?;
+        let naming = config.naming.clone().unwrap_or_else(|| "%gs%i".to_string());
+        let split = config.split_by_group.unwrap_or(true);
+        let packs_dir = packs_dir(config.output.as_deref().unwrap_or("assets"));
+        let pack_files = list_files(&packs_dir);
+
+        let mut groups = Vec::new();
+        let mut grouped_dirs = Vec::new();
+        for (name, dir) in &config.groups {
+            let group_config: GroupConfig = fs::read_to_string(root.join(dir).join("config.toml"))
+                .ok()
+                .and_then(|file| toml::from_str(&file).ok())
+                .unwrap_or_default();
+            let codec = match (&group_config.compression, group_config.compression_level) {
+                (Some(codec), Some(level)) => format!("{codec} {level}"),
+                (Some(codec), None) => codec.clone(),
+                (None, _) => "none".to_string(),
+            };
+            let pack_group = if split { name.as_str() } else { "asset" };
+            groups.push(GroupInfo {
+                name: name.clone(),
+                dir: dir.clone(),
+                codec,
+                files: scan_files(&root, Path::new(dir), &config.exclude, &[]),
+                packs: pack_files
+                    .iter()
+                    .filter(|(file, _)| matches_naming(&naming, pack_group, file))
+                    .cloned()
+                    .collect(),
+            });
+            grouped_dirs.push(dir.clone());
+        }
+        // Files outside of every group directory.
+        let skip: Vec<String> = grouped_dirs
+            .into_iter()
+            .chain(SANDBOX_FILES.map(String::from))
+            .collect();
+        let ungrouped = scan_files(&root, Path::new(""), &config.exclude, &skip);
+        if !ungrouped.is_empty() {
+            groups.push(GroupInfo {
+                name: "(ungrouped)".to_string(),
+                dir: String::new(),
+                codec: "none".to_string(),
+                files: ungrouped,
+                packs: Vec::new(),
+            });
+        }
+
+        let mut warnings = Vec::new();
+        if pack_files.is_empty() {
+            warnings.push(format!("No pack files found in {}", packs_dir.display()));
+        }
+        for path in &self.requested {
+            if let Err(e) = let_engine::asset_system::asset(path) {
+                warnings.push(format!("Requested asset {path} is missing: {e:?}"));
+            }
+        }
+        for group in &groups {
+            for file in &group.files {
+                if let (false, Err(e)) = (file.excluded, &file.load) {
+                    warnings.push(format!("{} is not packed: {e}", file.path));
+                }
+            }
+        }
+        Ok(Scan {
+            config,
+            packs_dir,
+            groups,
+            warnings,
+        })
+    }
+
+    pub fn ui(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            if ui.button("Scan").clicked() {
+                self.scan = Some(self.scan());
+            }
+            ui.label(format!("Assets directory: {}", assets_dir().display()));
+        });
+        ui.horizontal(|ui| {
+            ui.add(egui::TextEdit::singleline(&mut self.probe).hint_text("textures/test.png"));
+            if ui.button("Check path").clicked() && !self.probe.is_empty() {
+                self.requested.push(std::mem::take(&mut self.probe));
+                self.scan = Some(self.scan());
+            }
+        });
+        let scan = match &self.scan {
+            None => return,
+            Some(Err(e)) => {
+                ui.colored_label(
+                    Color32::LIGHT_RED,
+                    format!("Failed to read config.toml: {e}"),
+                );
+                return;
+            }
+            Some(Ok(scan)) => scan,
+        };
+
+        ui.separator();
+        let config = &scan.config;
+        ui.label(format!(
+            "max_size: {}   naming: {}   output: {}",
+            config
+                .max_size
+                .map(bytes)
+                .unwrap_or_else(|| "-".to_string()),
+            config.naming.as_deref().unwrap_or("-"),
+            scan.packs_dir.display(),
+        ));
+        if !config.exclude.is_empty() {
+            ui.label(format!("exclude: {}", config.exclude.join(", ")));
+        }
+        for warning in &scan.warnings {
+            ui.colored_label(Color32::YELLOW, warning);
+        }
+
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            for group in &scan.groups {
+                let (raw, packed) = (group.raw_size(), group.packed_size());
+                let ratio = if raw > 0 && packed > 0 {
+                    format!("{:.1}%", packed as f64 / raw as f64 * 100.0)
+                } else {
+                    "-".to_string()
+                };
+                let header = format!(
+                    "{} ({})  {}  {} files  {} -> {}  {ratio}",
+                    group.name,
+                    group.dir,
+                    group.codec,
+                    group.files.len(),
+                    bytes(raw),
+                    bytes(packed),
+                );
+                egui::CollapsingHeader::new(header)
+                    .id_salt(&group.name)
+                    .show(ui, |ui| group_ui(ui, group));
+            }
+        });
+    }
+}
+
+fn group_ui(ui: &mut egui::Ui, group: &GroupInfo) {
+    for (pack, size) in &group.packs {
+        ui.label(format!("pack {pack}: {}", bytes(*size)));
+    }
+    // Files are only attributable to a pack if the group did not get split.
+    let pack = match group.packs.as_slice() {
+        [] => "-".to_string(),
+        [(pack, _)] => pack.clone(),
+        _ => "split".to_string(),
+    };
+    let raw_size = group.raw_size();
+    let ratio = (raw_size > 0).then(|| group.packed_size() as f64 / raw_size as f64);
+    egui::Grid::new(("asset_files", &group.name))
+        .striped(true)
+        .show(ui, |ui| {
+            for header in [
+                "Path",
+                "Raw",
+                "Packed (est.)",
+                "Unpacked",
+                "Load time",
+                "Pack",
+            ] {
+                ui.strong(header);
+            }
+            ui.end_row();
+            for file in &group.files {
+                ui.label(&file.path);
+                ui.label(bytes(file.raw_size));
+                match &file.load {
+                    _ if file.excluded => {
+                        ui.label(RichText::new("excluded").weak());
+                        ui.label("");
+                        ui.label("");
+                        ui.label("");
+                    }
+                    Ok((size, time)) => {
+                        match ratio.filter(|_| !group.packs.is_empty()) {
+                            Some(ratio) => ui.label(format!(
+                                "~{}",
+                                bytes((file.raw_size as f64 * ratio) as u64)
+                            )),
+                            None => ui.label("-"),
+                        };
+                        ui.label(bytes(*size as u64));
+                        ui.label(format!("{:.3} ms", time.as_secs_f64() * 1000.0));
+                        let label = ui.label(&pack);
+                        if group.packs.len() > 1 {
+                            label.on_hover_text("One of the packs listed above");
+                        }
+                    }
+                    Err(_) => {
+                        ui.colored_label(Color32::YELLOW, "missing");
+                        ui.label("");
+                        ui.label("");
+                        ui.label("");
+                    }
+                }
+                ui.end_row();
+            }
+        });
+}
+
+/// Where the packer writes its output, next to the executable or in the working directory.
+fn packs_dir(output: &str) -> PathBuf {
+    std::env::current_exe()
+        .ok()
+        .and_then(|exe| Some(exe.parent()?.join(output)))
+        .filter(|dir| dir.is_dir())
+        .unwrap_or_else(|| PathBuf::from(output))
+}
+
+fn list_files(dir: &Path) -> Vec<(String, u64)> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut files: Vec<(String, u64)> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let metadata = entry.metadata().ok()?;
+            metadata.is_file().then(|| {
+                (
+                    entry.file_name().to_string_lossy().into_owned(),
+                    metadata.len(),
+                )
+            })
+        })
+        .collect();
+    files.sort();
+    files
+}
+
+/// Whether a pack file name matches the naming pattern for a group, like `textures0` for `%gs%i`.
+fn matches_naming(naming: &str, group: &str, file: &str) -> bool {
+    let stem = file.split('.').next().unwrap_or(file);
+    let pattern = naming.replace("%g", group);
+    let Some((prefix, suffix)) = pattern.split_once("%i") else {
+        return stem == pattern;
+    };
+    stem.len() > prefix.len() + suffix.len()
+        && stem.starts_with(prefix)
+        && stem.ends_with(suffix)
+        && stem[prefix.len()..stem.len() - suffix.len()]
+            .chars()
+            .all(|c| c.is_ascii_digit())
+}
+
+/// Every file below `dir`, except the packer configs and the `skip` paths,
+/// loaded through the asset system.
+fn scan_files(root: &Path, dir: &Path, exclude: &[String], skip: &[String]) -> Vec<FileInfo> {
+    let mut files = Vec::new();
+    let mut dirs = vec![dir.to_path_buf()];
+    while let Some(dir) = dirs.pop() {
+        let Ok(entries) = fs::read_dir(root.join(&dir)) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let relative = dir.join(entry.file_name());
+            let Ok(metadata) = entry.metadata() else {
+                continue;
+            };
+            if skip.iter().any(|skip| relative == Path::new(skip)) {
+                continue;
+            }
+            if metadata.is_dir() {
+                dirs.push(relative);
+                continue;
+            }
+            if entry.file_name() == "config.toml" {
+                continue;
+            }
+            let path = relative.to_string_lossy().replace('\\', "/");
+            let excluded = exclude
+                .iter()
+                .any(|excluded| path.starts_with(excluded.as_str()));
+            let start = Instant::now();
+            let load = let_engine::asset_system::asset(&path)
+                .map(|data| (data.len(), start.elapsed()))
+                .map_err(|e| format!("{e:?}"));
+            files.push(FileInfo {
+                path,
+                raw_size: metadata.len(),
+                excluded,
+                load,
+            });
+        }
+    }
+    files.sort_by(|a, b| a.path.cmp(&b.path));
+    files
+}
+
+fn bytes(size: u64) -> String {
+    match size {
+        0..1024 => format!("{size} B"),
+        1024..1_048_576 => format!("{:.1} KiB", size as f64 / 1024.0),
+        _ => format!("{:.2} MiB", size as f64 / 1_048_576.0),
+    }
+}
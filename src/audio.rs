@@ -0,0 +1,37 @@
+use std::io::Cursor;
+
+use rodio::{Decoder, OutputStream, OutputStreamHandle, Sink};
+
+use crate::error::{Result, SandboxError};
+
+/// Plays sound effects and previews on the default output device.
+pub struct Audio {
+    /// The stream stops playing when dropped, so it is kept with its handle.
+    output: Option<(OutputStream, OutputStreamHandle)>,
+    playing: Vec<Sink>,
+}
+
+impl Audio {
+    pub fn new() -> Self {
+        let output = OutputStream::try_default()
+            .map_err(|e| log::warn!("No audio output: {e}"))
+            .ok();
+        Self {
+            output,
+            playing: Vec::new(),
+        }
+    }
+
+    /// Decodes and plays an mp3, ogg, wav or flac file.
+    pub fn play(&mut self, name: &str, bytes: Vec<u8>) -> Result<()> {
+        let Some((_, handle)) = &self.output else {
+            return Err(SandboxError::Audio("no audio output".to_string()));
+        };
+        let source = Decoder::new(Cursor::new(bytes)).map_err(SandboxError::asset(name))?;
+        let sink = Sink::try_new(handle).map_err(SandboxError::audio)?;
+        sink.append(source);
+        self.playing.retain(|sink| !sink.empty());
+        self.playing.push(sink);
+        Ok(())
+    }
+}
@@ -0,0 +1,422 @@
+use std::{
+    fs,
+    path::PathBuf,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+use let_engine::prelude::*;
+use serde::Serialize;
+
+use crate::{
+    Ctx,
+    error::Result,
+    sandbox::{BodySettings, Sandbox},
+};
+
+/// Top of the platform spawned in `Game::new`.
+const GROUND: f32 = 0.9;
+
+/// Set by the build script.
+const ENGINE_VERSION: &str = env!("LET_ENGINE_VERSION");
+const FAST_MATH: bool = cfg!(feature = "fast-math");
+
+#[derive(Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum Pattern {
+    /// Objects drop in from above over the first half of the run.
+    Rain,
+    Pyramid,
+    Grid,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum ReportFormat {
+    Csv,
+    Json,
+}
+
+#[derive(Clone, Copy, Serialize)]
+struct Sample {
+    time: f32,
+    objects: usize,
+    frame_time_ms: f32,
+    /// Time spent building the frame's UI.
+    ui_time_ms: f32,
+    /// Time between the end of the last frame's UI and the start of this one. The engine
+    /// draws and presents in it, but it also holds ticks and any wait for vsync or the
+    /// fps cap, so it is not the render time.
+    between_frames_ms: f32,
+    tick_time_ms: f32,
+    fps: f64,
+}
+
+/// Average frame time for all samples taken at a given object count.
+#[derive(Serialize)]
+struct Bucket {
+    objects: usize,
+    samples: usize,
+    avg_frame_time_ms: f32,
+    max_frame_time_ms: f32,
+    avg_between_frames_ms: f32,
+    avg_tick_time_ms: f32,
+}
+
+#[derive(Serialize)]
+struct Report<'a> {
+    label: &'a str,
+    version: &'a str,
+    engine_version: &'a str,
+    fast_math: bool,
+    pattern: Pattern,
+    count: usize,
+    box_size: f32,
+    duration: f32,
+    buckets: Vec<Bucket>,
+    samples: &'a [Sample],
+}
+
+struct Run {
+    started: Instant,
+    last_frame: Instant,
+    objects: Vec<ObjectId>,
+    samples: Vec<Sample>,
+}
+
+pub struct Benchmark {
+    pub label: String,
+    pub count: usize,
+    pub pattern: Pattern,
+    pub duration: f32,
+    pub box_size: f32,
+    pub format: ReportFormat,
+    pub cleanup: bool,
+    run: Option<Run>,
+    last_tick_time: Duration,
+    status: String,
+}
+
+impl Benchmark {
+    pub fn new() -> Self {
+        Self {
+            label: String::new(),
+            count: 500,
+            pattern: Pattern::Rain,
+            duration: 20.0,
+            box_size: 0.03,
+            format: ReportFormat::Csv,
+            cleanup: true,
+            run: None,
+            last_tick_time: Duration::ZERO,
+            status: String::new(),
+        }
+    }
+
+    pub fn running(&self) -> bool {
+        self.run.is_some()
+    }
+
+    /// Starts a run. Objects spawned before a failure are removed again.
+    pub fn start(&mut self, ctx: &Ctx, sandbox: &mut Sandbox) -> Result<()> {
+        let now = Instant::now();
+        let mut run = Run {
+            started: now,
+            last_frame: now,
+            objects: Vec::with_capacity(self.count),
+            samples: Vec::new(),
+        };
+        // Rain spawns gradually in `update`, everything else at once.
+        if self.pattern != Pattern::Rain
+            && let Err(e) = self.spawn(ctx, sandbox, &mut run, self.count)
+        {
+            Self::remove_objects(ctx, run.objects, sandbox);
+            self.status = "Failed to start".to_string();
+            return Err(e);
+        }
+        self.run = Some(run);
+        self.status = "Running...".to_string();
+        Ok(())
+    }
+
+    /// Spawns the next rain batch and finishes the run when the duration is over.
+    ///
+    /// A failed spawn cancels the run.
+    pub fn update(&mut self, ctx: &Ctx, sandbox: &mut Sandbox, tick_time: Duration) -> Result<()> {
+        self.last_tick_time = tick_time;
+        let Some(mut run) = self.run.take() else {
+            return Ok(());
+        };
+        let elapsed = run.started.elapsed().as_secs_f32();
+
+        if self.pattern == Pattern::Rain {
+            let progress = (elapsed / (self.duration * 0.5)).min(1.0);
+            let target = (progress * self.count as f32) as usize;
+            if let Err(e) = self.spawn(ctx, sandbox, &mut run, target) {
+                Self::remove_objects(ctx, run.objects, sandbox);
+                self.status = "Cancelled after an error".to_string();
+                return Err(e);
+            }
+        }
+
+        if elapsed >= self.duration {
+            self.finish(ctx, sandbox, run);
+        } else {
+            self.run = Some(run);
+        }
+        Ok(())
+    }
+
+    /// Spawns objects until the run has `target` of them.
+    fn spawn(&self, ctx: &Ctx, sandbox: &mut Sandbox, run: &mut Run, target: usize) -> Result<()> {
+        for i in run.objects.len()..target {
+            let id = sandbox.spawn_square(ctx, self.transform(i), BodySettings::default())?;
+            run.objects.push(id);
+        }
+        Ok(())
+    }
+
+    /// Records one sample per rendered frame, after the UI that started at `ui_start`.
+    pub fn record_frame(&mut self, ctx: &Ctx, ui_start: Instant) {
+        let Some(run) = &mut self.run else {
+            return;
+        };
+        let now = Instant::now();
+        run.samples.push(Sample {
+            time: (now - run.started).as_secs_f32(),
+            objects: run.objects.len(),
+            frame_time_ms: (now - run.last_frame).as_secs_f32() * 1000.0,
+            ui_time_ms: (now - ui_start).as_secs_f32() * 1000.0,
+            between_frames_ms: ui_start
+                .saturating_duration_since(run.last_frame)
+                .as_secs_f32()
+                * 1000.0,
+            tick_time_ms: self.last_tick_time.as_secs_f32() * 1000.0,
+            fps: ctx.time.fps(),
+        });
+        run.last_frame = now;
+    }
+
+    pub fn cancel(&mut self, ctx: &Ctx, sandbox: &mut Sandbox) {
+        if let Some(run) = self.run.take() {
+            Self::remove_objects(ctx, run.objects, sandbox);
+            self.status = "Cancelled".to_string();
+        }
+    }
+
+    fn finish(&mut self, ctx: &Ctx, sandbox: &mut Sandbox, run: Run) {
+        self.status = match self.write_report(&run.samples) {
+            Ok(path) => format!("Report written to {}", path.display()),
+            Err(e) => format!("Failed to write report: {e}"),
+        };
+        log::info!("Benchmark finished. {}", self.status);
+        if self.cleanup {
+            Self::remove_objects(ctx, run.objects, sandbox);
+        }
+    }
+
+    fn remove_objects(ctx: &Ctx, objects: Vec<ObjectId>, sandbox: &mut Sandbox) {
+        for id in objects {
+            sandbox.remove_object(ctx, id);
+        }
+    }
+
+    /// Position of the object with the given index in the selected pattern.
+    fn transform(&self, index: usize) -> Transform {
+        let size = self.box_size;
+        let spacing = size * 2.2;
+        let position = match self.pattern {
+            Pattern::Rain => {
+                // Deterministic scatter so runs stay comparable between builds.
+                let x = ((index * 7919) % 997) as f32 / 997.0;
+                vec2(x * 9.6 - 4.8, -1.5 - (index % 5) as f32 * spacing)
+            }
+            Pattern::Pyramid => {
+                let rows = pyramid_rows(self.count);
+                let mut row = 0;
+                let mut first = 0;
+                while first + rows - row <= index {
+                    first += rows - row;
+                    row += 1;
+                }
+                let width = rows - row;
+                let column = index - first;
+                vec2(
+                    (column as f32 - (width as f32 - 1.0) * 0.5) * spacing,
+                    GROUND - size - row as f32 * size * 2.0,
+                )
+            }
+            Pattern::Grid => {
+                let columns = (self.count as f32).sqrt().ceil() as usize;
+                let column = index % columns;
+                let row = index / columns;
+                vec2(
+                    (column as f32 - (columns as f32 - 1.0) * 0.5) * spacing,
+                    GROUND - size - row as f32 * spacing,
+                )
+            }
+        };
+        (position, vec2(size, size), 0.0).into()
+    }
+
+    fn write_report(&self, samples: &[Sample]) -> std::io::Result<PathBuf> {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        let buckets = buckets(samples);
+
+        let (path, contents) = match self.format {
+            ReportFormat::Csv => {
+                let mut csv = format!(
+                    "# label: {}\n# version: {}\n# engine version: {}\n# fast-math: {}\n\
+                     # pattern: {}\n# count: {}\n# duration: {}\n",
+                    self.label,
+                    env!("CARGO_PKG_VERSION"),
+                    ENGINE_VERSION,
+                    FAST_MATH,
+                    self.pattern.name(),
+                    self.count,
+                    self.duration,
+                );
+                csv += "time,objects,frame_time_ms,ui_time_ms,between_frames_ms,tick_time_ms,fps\n";
+                for s in samples {
+                    csv += &format!(
+                        "{:.4},{},{:.4},{:.4},{:.4},{:.4},{:.2}\n",
+                        s.time,
+                        s.objects,
+                        s.frame_time_ms,
+                        s.ui_time_ms,
+                        s.between_frames_ms,
+                        s.tick_time_ms,
+                        s.fps
+                    );
+                }
+                csv += "\nobjects,samples,avg_frame_time_ms,max_frame_time_ms,\
+                        avg_between_frames_ms,avg_tick_time_ms\n";
+                for b in &buckets {
+                    csv += &format!(
+                        "{},{},{:.4},{:.4},{:.4},{:.4}\n",
+                        b.objects,
+                        b.samples,
+                        b.avg_frame_time_ms,
+                        b.max_frame_time_ms,
+                        b.avg_between_frames_ms,
+                        b.avg_tick_time_ms
+                    );
+                }
+                (format!("benchmark-{timestamp}.csv"), csv)
+            }
+            ReportFormat::Json => {
+                let report = Report {
+                    label: &self.label,
+                    version: env!("CARGO_PKG_VERSION"),
+                    engine_version: ENGINE_VERSION,
+                    fast_math: FAST_MATH,
+                    pattern: self.pattern,
+                    count: self.count,
+                    box_size: self.box_size,
+                    duration: self.duration,
+                    buckets,
+                    samples,
+                };
+                (
+                    format!("benchmark-{timestamp}.json"),
+                    serde_json::to_string_pretty(&report)?,
+                )
+            }
+        };
+        let path = PathBuf::from(path);
+        fs::write(&path, contents)?;
+        Ok(path)
+    }
+
+    pub fn ui(&mut self, ui: &mut egui::Ui) -> BenchmarkAction {
+        let mut action = BenchmarkAction::None;
+        let running = self.running();
+        ui.add_enabled_ui(!running, |ui| {
+            ui.horizontal(|ui| {
+                ui.label("Label");
+                ui.text_edit_singleline(&mut self.label);
+            });
+            ui.horizontal(|ui| {
+                for pattern in [Pattern::Rain, Pattern::Pyramid, Pattern::Grid] {
+                    ui.radio_value(&mut self.pattern, pattern, pattern.name());
+                }
+            });
+            ui.add(egui::Slider::new(&mut self.count, 10..=5000).text("Objects"));
+            ui.add(egui::Slider::new(&mut self.box_size, 0.005..=0.2).text("Box size"));
+            ui.add(egui::Slider::new(&mut self.duration, 5.0..=120.0).text("Duration (s)"));
+            ui.horizontal(|ui| {
+                ui.radio_value(&mut self.format, ReportFormat::Csv, "CSV");
+                ui.radio_value(&mut self.format, ReportFormat::Json, "JSON");
+            });
+            ui.checkbox(&mut self.cleanup, "Remove objects when finished");
+        });
+        ui.horizontal(|ui| {
+            if running {
+                if ui.button("Cancel").clicked() {
+                    action = BenchmarkAction::Cancel;
+                }
+                if let Some(run) = &self.run {
+                    let progress = run.started.elapsed().as_secs_f32() / self.duration;
+                    ui.add(egui::ProgressBar::new(progress.min(1.0)).show_percentage());
+                }
+            } else if ui.button("Start").clicked() {
+                action = BenchmarkAction::Start;
+            }
+        });
+        if !self.status.is_empty() {
+            ui.label(&self.status);
+        }
+        action
+    }
+}
+
+pub enum BenchmarkAction {
+    None,
+    Start,
+    Cancel,
+}
+
+impl Pattern {
+    fn name(self) -> &'static str {
+        match self {
+            Pattern::Rain => "Rain",
+            Pattern::Pyramid => "Pyramid",
+            Pattern::Grid => "Grid",
+        }
+    }
+}
+
+/// Smallest number of rows a pyramid needs to hold `count` objects.
+fn pyramid_rows(count: usize) -> usize {
+    let mut rows = 0;
+    while rows * (rows + 1) / 2 < count {
+        rows += 1;
+    }
+    rows
+}
+
+fn buckets(samples: &[Sample]) -> Vec<Bucket> {
+    let mut buckets: Vec<Bucket> = Vec::new();
+    for s in samples {
+        match buckets.last_mut() {
+            Some(b) if b.objects == s.objects => {
+                let n = b.samples as f32;
+                b.avg_frame_time_ms = (b.avg_frame_time_ms * n + s.frame_time_ms) / (n + 1.0);
+                b.avg_between_frames_ms =
+                    (b.avg_between_frames_ms * n + s.between_frames_ms) / (n + 1.0);
+                b.avg_tick_time_ms = (b.avg_tick_time_ms * n + s.tick_time_ms) / (n + 1.0);
+                b.max_frame_time_ms = b.max_frame_time_ms.max(s.frame_time_ms);
+                b.samples += 1;
+            }
+            _ => buckets.push(Bucket {
+                objects: s.objects,
+                samples: 1,
+                avg_frame_time_ms: s.frame_time_ms,
+                max_frame_time_ms: s.frame_time_ms,
+                avg_between_frames_ms: s.between_frames_ms,
+                avg_tick_time_ms: s.tick_time_ms,
+            }),
+        }
+    }
+    buckets
+}
@@ -0,0 +1,420 @@
+use std::{
+    collections::{BTreeMap, BTreeSet, HashSet},
+    fs,
+    path::PathBuf,
+};
+
+use let_engine::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{Ctx, assets_dir};
+
+const MOUSE_BUTTONS: [MouseButton; 5] = [
+    MouseButton::Left,
+    MouseButton::Right,
+    MouseButton::Middle,
+    MouseButton::Back,
+    MouseButton::Forward,
+];
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Action {
+    /// Uses the active tool.
+    Primary,
+    /// The active tool's secondary function.
+    Secondary,
+    Pan,
+    SpawnTool,
+    DeleteTool,
+    SelectTool,
+    JointTool,
+    DragTool,
+    PanTool,
+    ExplosionTool,
+    FieldTool,
+    RopeTool,
+    SoftBodyTool,
+    VehicleTool,
+    PathTool,
+    ConveyorTool,
+    NextTool,
+    ZoomIn,
+    ZoomOut,
+    Pause,
+    Fullscreen,
+    Console,
+    Exit,
+}
+
+impl Action {
+    pub const ALL: [Action; 23] = [
+        Action::Primary,
+        Action::Secondary,
+        Action::Pan,
+        Action::SpawnTool,
+        Action::DeleteTool,
+        Action::SelectTool,
+        Action::JointTool,
+        Action::DragTool,
+        Action::PanTool,
+        Action::ExplosionTool,
+        Action::FieldTool,
+        Action::RopeTool,
+        Action::SoftBodyTool,
+        Action::VehicleTool,
+        Action::PathTool,
+        Action::ConveyorTool,
+        Action::NextTool,
+        Action::ZoomIn,
+        Action::ZoomOut,
+        Action::Pause,
+        Action::Fullscreen,
+        Action::Console,
+        Action::Exit,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Action::Primary => "Use tool",
+            Action::Secondary => "Secondary tool action",
+            Action::Pan => "Pan camera",
+            Action::SpawnTool => "Spawn tool",
+            Action::DeleteTool => "Delete tool",
+            Action::SelectTool => "Select tool",
+            Action::JointTool => "Joint tool",
+            Action::DragTool => "Drag tool",
+            Action::PanTool => "Pan tool",
+            Action::ExplosionTool => "Explosion tool",
+            Action::FieldTool => "Field tool",
+            Action::RopeTool => "Rope tool",
+            Action::SoftBodyTool => "Soft body tool",
+            Action::VehicleTool => "Vehicle tool",
+            Action::PathTool => "Path tool",
+            Action::ConveyorTool => "Conveyor tool",
+            Action::NextTool => "Next tool",
+            Action::ZoomIn => "Zoom in",
+            Action::ZoomOut => "Zoom out",
+            Action::Pause => "Pause",
+            Action::Fullscreen => "Fullscreen",
+            Action::Console => "Command console",
+            Action::Exit => "Exit",
+        }
+    }
+}
+
+/// A key or mouse button an action is bound to.
+///
+/// Keys are stored by their logical name, so bindings follow the keyboard layout.
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Binding {
+    Key(String),
+    Mouse(String),
+}
+
+impl Binding {
+    fn key(name: &str) -> Self {
+        Self::Key(name.to_string())
+    }
+
+    fn mouse(button: MouseButton) -> Self {
+        Self::Mouse(mouse_button_name(button))
+    }
+}
+
+impl std::fmt::Display for Binding {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Binding::Key(key) => write!(f, "{key}"),
+            Binding::Mouse(button) => write!(f, "Mouse {button}"),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct BindingsFile {
+    bindings: BTreeMap<Action, Binding>,
+    /// Actions cleared by the user, which stay unbound instead of getting their default.
+    #[serde(default)]
+    unbound: BTreeSet<Action>,
+}
+
+impl BindingsFile {
+    fn new(map: &BTreeMap<Action, Binding>) -> Self {
+        Self {
+            bindings: map.clone(),
+            unbound: Action::ALL
+                .into_iter()
+                .filter(|action| !map.contains_key(action))
+                .collect(),
+        }
+    }
+
+    /// Overrides the bindings in `map`, usually the defaults, with the file's.
+    fn apply(self, map: &mut BTreeMap<Action, Binding>) {
+        map.extend(self.bindings);
+        for action in self.unbound {
+            map.remove(&action);
+        }
+    }
+}
+
+/// The input action map, loaded from `bindings.toml` in the assets directory.
+pub struct Bindings {
+    map: BTreeMap<Action, Binding>,
+    held_keys: HashSet<String>,
+    down: HashSet<Action>,
+    capturing: Option<Action>,
+    /// Mouse buttons held when a capture started, ignored until released.
+    capture_held: Vec<MouseButton>,
+    status: String,
+}
+
+impl Bindings {
+    pub fn load() -> Self {
+        let mut bindings = Self {
+            map: Self::defaults(),
+            held_keys: HashSet::new(),
+            down: HashSet::new(),
+            capturing: None,
+            capture_held: Vec::new(),
+            status: String::new(),
+        };
+        match fs::read_to_string(Self::path()) {
+            Ok(file) => match toml::from_str::<BindingsFile>(&file) {
+                Ok(file) => file.apply(&mut bindings.map),
+                Err(e) => {
+                    bindings.status = format!("Failed to parse bindings: {e}");
+                    log::warn!("{}", bindings.status);
+                }
+            },
+            Err(_) => bindings.status = "Using default bindings".to_string(),
+        }
+        bindings
+    }
+
+    pub fn save(&mut self) {
+        self.status = match toml::to_string_pretty(&BindingsFile::new(&self.map))
+            .map_err(|e| e.to_string())
+            .and_then(|file| fs::write(Self::path(), file).map_err(|e| e.to_string()))
+        {
+            Ok(()) => format!("Saved to {}", Self::path().display()),
+            Err(e) => format!("Failed to save bindings: {e}"),
+        };
+    }
+
+    fn path() -> PathBuf {
+        assets_dir().join("bindings.toml")
+    }
+
+    fn defaults() -> BTreeMap<Action, Binding> {
+        BTreeMap::from([
+            (Action::Primary, Binding::mouse(MouseButton::Left)),
+            (Action::Secondary, Binding::mouse(MouseButton::Right)),
+            (Action::Pan, Binding::mouse(MouseButton::Middle)),
+            (Action::SpawnTool, Binding::key("F1")),
+            (Action::DeleteTool, Binding::key("F2")),
+            (Action::SelectTool, Binding::key("F3")),
+            (Action::JointTool, Binding::key("F4")),
+            (Action::DragTool, Binding::key("F5")),
+            (Action::PanTool, Binding::key("F6")),
+            (Action::ExplosionTool, Binding::key("F7")),
+            (Action::FieldTool, Binding::key("F9")),
+            (Action::RopeTool, Binding::key("F10")),
+            (Action::SoftBodyTool, Binding::key("F12")),
+            (Action::VehicleTool, Binding::key("Insert")),
+            (Action::PathTool, Binding::key("Home")),
+            (Action::ConveyorTool, Binding::key("End")),
+            (Action::NextTool, Binding::key("Delete")),
+            (Action::ZoomIn, Binding::key("PageUp")),
+            (Action::ZoomOut, Binding::key("PageDown")),
+            (Action::Pause, Binding::key("F8")),
+            (Action::Fullscreen, Binding::key("F11")),
+            (Action::Console, Binding::key("ScrollLock")),
+            (Action::Exit, Binding::key("Escape")),
+        ])
+    }
+
+    /// Names of the keys currently held, as used in `bindings.toml`.
+    pub fn held_keys(&self) -> &HashSet<String> {
+        &self.held_keys
+    }
+
+    /// Whether the action's key or button is currently held.
+    pub fn down(&self, action: Action) -> bool {
+        self.down.contains(&action)
+    }
+
+    /// Polls mouse buttons and refreshes the set of held actions.
+    ///
+    /// `extra` are actions held by other devices, like a gamepad.
+    /// Call once at the start of every tick.
+    pub fn update(&mut self, ctx: &Ctx, extra: &HashSet<Action>) {
+        if let Some(action) = self.capturing {
+            self.capture_held
+                .retain(|button| ctx.input.mouse_down(button));
+            if let Some(button) = MOUSE_BUTTONS
+                .into_iter()
+                .find(|button| ctx.input.mouse_down(button) && !self.capture_held.contains(button))
+            {
+                self.map.insert(action, Binding::mouse(button));
+                self.capturing = None;
+            }
+        }
+
+        self.down = self
+            .map
+            .iter()
+            .filter(|(_, binding)| match binding {
+                Binding::Key(key) => self.held_keys.contains(key),
+                Binding::Mouse(name) => MOUSE_BUTTONS.iter().any(|button| {
+                    mouse_button_name(*button) == *name && ctx.input.mouse_down(button)
+                }),
+            })
+            .map(|(action, _)| *action)
+            .chain(extra.iter().copied())
+            .collect();
+    }
+
+    /// Tracks held keys and returns the actions triggered by a key press.
+    ///
+    /// While rebinding, the key press is consumed and assigned instead.
+    pub fn key_event(&mut self, key: &Key, state: ElementState) -> Vec<Action> {
+        let Some(name) = key_name(key) else {
+            return vec![];
+        };
+        if state == ElementState::Released {
+            self.held_keys.remove(&name);
+            return vec![];
+        }
+        if let Some(action) = self.capturing.take() {
+            self.map.insert(action, Binding::Key(name));
+            return vec![];
+        }
+        let repeat = !self.held_keys.insert(name.clone());
+        if repeat {
+            return vec![];
+        }
+        let binding = Binding::Key(name);
+        self.map
+            .iter()
+            .filter(|(_, b)| **b == binding)
+            .map(|(action, _)| *action)
+            .collect()
+    }
+
+    pub fn capturing(&self) -> bool {
+        self.capturing.is_some()
+    }
+
+    pub fn ui(&mut self, ui: &mut egui::Ui, ctx: &Ctx) {
+        egui::Grid::new("bindings").striped(true).show(ui, |ui| {
+            for action in Action::ALL {
+                ui.label(action.name());
+                if self.capturing == Some(action) {
+                    if ui.button("Press a key or button...").clicked() {
+                        self.capturing = None;
+                    }
+                } else {
+                    let text = self
+                        .map
+                        .get(&action)
+                        .map(|b| b.to_string())
+                        .unwrap_or_else(|| "Unbound".to_string());
+                    if ui.button(text).clicked() {
+                        self.capturing = Some(action);
+                        self.capture_held = MOUSE_BUTTONS
+                            .into_iter()
+                            .filter(|button| ctx.input.mouse_down(button))
+                            .collect();
+                    }
+                }
+                if ui.small_button("Clear").clicked() {
+                    self.map.remove(&action);
+                }
+                ui.end_row();
+            }
+        });
+        ui.horizontal(|ui| {
+            if ui.button("Save").clicked() {
+                self.save();
+            }
+            if ui.button("Reload").clicked() {
+                *self = Self::load();
+            }
+            if ui.button("Defaults").clicked() {
+                self.map = Self::defaults();
+            }
+        });
+        if !self.status.is_empty() {
+            ui.label(&self.status);
+        }
+    }
+}
+
+fn key_name(key: &Key) -> Option<String> {
+    match key {
+        Key::Named(named) => Some(format!("{named:?}")),
+        Key::Character(c) => Some(c.to_lowercase()),
+        _ => None,
+    }
+}
+
+fn mouse_button_name(button: MouseButton) -> String {
+    match button {
+        MouseButton::Other(n) => format!("Other{n}"),
+        button => format!("{button:?}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn round_trip(map: &BTreeMap<Action, Binding>) -> BTreeMap<Action, Binding> {
+        let file = toml::to_string_pretty(&BindingsFile::new(map)).unwrap();
+        let mut loaded = Bindings::defaults();
+        toml::from_str::<BindingsFile>(&file)
+            .unwrap()
+            .apply(&mut loaded);
+        loaded
+    }
+
+    #[test]
+    fn shipped_file_matches_the_defaults() {
+        let file: BindingsFile = toml::from_str(include_str!("../assets/bindings.toml")).unwrap();
+        assert!(file.unbound.is_empty());
+        assert!(file.bindings == Bindings::defaults());
+    }
+
+    #[test]
+    fn every_action_has_a_default() {
+        let defaults = Bindings::defaults();
+        assert!(
+            Action::ALL
+                .iter()
+                .all(|action| defaults.contains_key(action))
+        );
+    }
+
+    #[test]
+    fn cleared_bindings_stay_unbound() {
+        let mut map = Bindings::defaults();
+        map.remove(&Action::Console);
+        map.insert(Action::Pause, Binding::key("p"));
+        let loaded = round_trip(&map);
+        assert!(!loaded.contains_key(&Action::Console));
+        assert!(loaded == map);
+    }
+
+    /// Printable keys would fire while typing into a text field.
+    #[test]
+    fn no_default_is_a_printable_key() {
+        for binding in Bindings::defaults().values() {
+            if let Binding::Key(key) = binding {
+                assert!(key.chars().count() > 1, "{key} is typed into text fields");
+            }
+        }
+    }
+}
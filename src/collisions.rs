@@ -0,0 +1,205 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use egui::{Color32, RichText};
+use let_engine::prelude::*;
+
+use crate::{Ctx, error::Result, sandbox::Sandbox};
+
+/// How many events the log keeps.
+const CAPACITY: usize = 1000;
+
+#[derive(Clone, Copy, PartialEq)]
+enum EventKind {
+    ContactStarted,
+    ContactStopped,
+    IntersectionStarted,
+    IntersectionStopped,
+}
+
+impl EventKind {
+    const ALL: [EventKind; 4] = [
+        EventKind::ContactStarted,
+        EventKind::ContactStopped,
+        EventKind::IntersectionStarted,
+        EventKind::IntersectionStopped,
+    ];
+
+    fn name(self) -> &'static str {
+        match self {
+            EventKind::ContactStarted => "Contact started",
+            EventKind::ContactStopped => "Contact stopped",
+            EventKind::IntersectionStarted => "Intersection started",
+            EventKind::IntersectionStopped => "Intersection stopped",
+        }
+    }
+
+    fn color(self) -> Color32 {
+        match self {
+            EventKind::ContactStarted => Color32::LIGHT_GREEN,
+            EventKind::ContactStopped => Color32::DARK_GREEN,
+            EventKind::IntersectionStarted => Color32::LIGHT_BLUE,
+            EventKind::IntersectionStopped => Color32::GRAY,
+        }
+    }
+}
+
+struct Event {
+    tick: u64,
+    kind: EventKind,
+    /// Sandbox numbers, `None` for objects the sandbox did not spawn.
+    a: Option<u32>,
+    b: Option<u32>,
+    /// Total contact impulse between the pair during the tick, `None` when the contact
+    /// was too soft to be reported.
+    impulse: Option<f32>,
+}
+
+/// Collects the collision events the engine reports and shows them in a window.
+pub struct CollisionLog {
+    events: VecDeque<Event>,
+    tick: u64,
+    paused: bool,
+    /// Only show events involving this object number.
+    filter: Option<u32>,
+    shown: [bool; 4],
+}
+
+impl CollisionLog {
+    pub fn new() -> Self {
+        Self {
+            events: VecDeque::with_capacity(CAPACITY),
+            tick: 0,
+            paused: false,
+            filter: None,
+            shown: [true; 4],
+        }
+    }
+
+    /// Drains this tick's events, updating sensor occupancy and belt contacts on the way.
+    ///
+    /// Call once per tick, after the physics step had a chance to produce events.
+    pub fn record(&mut self, ctx: &Ctx, sandbox: &mut Sandbox, dt: f32) -> Result<()> {
+        self.tick += 1;
+        let collisions = ctx.scene.root_layer_mut().take_collision_events();
+        let forces = ctx.scene.root_layer_mut().take_contact_force_events();
+
+        // Only pairs that get logged this tick need their impulse.
+        let logged: HashSet<(ObjectId, ObjectId)> = if self.paused {
+            HashSet::new()
+        } else {
+            collisions
+                .iter()
+                .map(|event| match *event {
+                    CollisionEvent::Started(a, b, _) | CollisionEvent::Stopped(a, b, _) => (a, b),
+                })
+                .collect()
+        };
+        let mut impulses: HashMap<(ObjectId, ObjectId), f32> = HashMap::new();
+        for force in forces {
+            let pair = (force.a, force.b);
+            if logged.contains(&pair) || logged.contains(&(force.b, force.a)) {
+                *impulses.entry(pair).or_default() += force.total_force_magnitude * dt;
+            }
+        }
+
+        for event in collisions {
+            let (a, b, started, sensor) = match event {
+                CollisionEvent::Started(a, b, flags) => {
+                    (a, b, true, flags.contains(CollisionEventFlags::SENSOR))
+                }
+                CollisionEvent::Stopped(a, b, flags) => {
+                    (a, b, false, flags.contains(CollisionEventFlags::SENSOR))
+                }
+            };
+            let kind = match (sensor, started) {
+                (false, true) => EventKind::ContactStarted,
+                (false, false) => EventKind::ContactStopped,
+                (true, true) => EventKind::IntersectionStarted,
+                (true, false) => EventKind::IntersectionStopped,
+            };
+            if sensor {
+                sandbox.set_occupant(ctx, a, b, started)?;
+                sandbox.set_occupant(ctx, b, a, started)?;
+            } else {
+                sandbox.set_belt_contact(a, b, started);
+                sandbox.set_belt_contact(b, a, started);
+            }
+            if self.paused {
+                continue;
+            }
+            let impulse = impulses
+                .get(&(a, b))
+                .or_else(|| impulses.get(&(b, a)))
+                .copied();
+            if self.events.len() == CAPACITY {
+                self.events.pop_front();
+            }
+            self.events.push_back(Event {
+                tick: self.tick,
+                kind,
+                a: sandbox.number(a),
+                b: sandbox.number(b),
+                impulse,
+            });
+        }
+        Ok(())
+    }
+
+    fn visible(&self, event: &Event) -> bool {
+        let kind = EventKind::ALL.iter().position(|kind| *kind == event.kind);
+        kind.is_some_and(|kind| self.shown[kind])
+            && self
+                .filter
+                .is_none_or(|number| event.a == Some(number) || event.b == Some(number))
+    }
+
+    pub fn ui(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            let mut filtered = self.filter.is_some();
+            ui.checkbox(&mut filtered, "Object");
+            let mut number = self.filter.unwrap_or_default();
+            ui.add_enabled(filtered, egui::DragValue::new(&mut number).prefix("#"));
+            self.filter = filtered.then_some(number);
+            ui.toggle_value(&mut self.paused, "Pause");
+            if ui.button("Clear").clicked() {
+                self.events.clear();
+            }
+        });
+        ui.horizontal(|ui| {
+            for (kind, shown) in EventKind::ALL.iter().zip(&mut self.shown) {
+                ui.checkbox(shown, kind.name());
+            }
+        });
+        ui.separator();
+
+        let events: Vec<&Event> = self
+            .events
+            .iter()
+            .filter(|event| self.visible(event))
+            .collect();
+        let number = |number: Option<u32>| match number {
+            Some(number) => format!("#{number}"),
+            None => "-".to_string(),
+        };
+        let row_height = ui.text_style_height(&egui::TextStyle::Monospace);
+        egui::ScrollArea::vertical()
+            .auto_shrink(false)
+            .stick_to_bottom(true)
+            .show_rows(ui, row_height, events.len(), |ui, rows| {
+                for event in &events[rows] {
+                    let impulse = event
+                        .impulse
+                        .map(|impulse| format!("{impulse:.5}"))
+                        .unwrap_or_default();
+                    let line = format!(
+                        "{:>8} {:<21} {:>5} {:>5} {impulse}",
+                        event.tick,
+                        event.kind.name(),
+                        number(event.a),
+                        number(event.b),
+                    );
+                    ui.label(RichText::new(line).monospace().color(event.kind.color()));
+                }
+            });
+    }
+}
@@ -0,0 +1,389 @@
+use std::collections::VecDeque;
+
+use let_engine::prelude::*;
+
+use crate::sandbox::{BodySettings, JointKind};
+
+/// How many output lines the console keeps.
+const OUTPUT_LINES: usize = 200;
+
+/// Command names with their usage, for `help` and autocompletion.
+const COMMANDS: [(&str, &str); 13] = [
+    ("help", "help"),
+    (
+        "spawn",
+        "spawn box <x> <y> <width> <height> [fixed] [sensor]",
+    ),
+    ("gravity", "gravity <x> <y>"),
+    ("timescale", "timescale <scale>"),
+    ("physics", "physics <parameter> <value>"),
+    ("clear", "clear"),
+    ("select", "select <number>"),
+    ("joint", "joint <fixed|revolute> <number> <number>"),
+    ("save", "save [name]"),
+    ("load", "load [name]"),
+    ("list", "list"),
+    ("remove", "remove <number>"),
+    ("velocity", "velocity <number> <x> <y>"),
+];
+
+/// A parsed console command. Objects are referred to by their sandbox number.
+pub enum Command {
+    Help,
+    /// Spawns a box. The size is in half extents, like everywhere else.
+    Spawn {
+        position: Vec2,
+        size: Vec2,
+        body: BodySettings,
+    },
+    Gravity(Vec2),
+    TimeScale(f32),
+    /// Sets one of the integration parameters, named like the fields of `PhysicsSettings`.
+    Physics {
+        parameter: String,
+        value: f32,
+    },
+    Clear,
+    Select(u32),
+    Joint {
+        kind: JointKind,
+        a: u32,
+        b: u32,
+    },
+    Save(String),
+    Load(String),
+    List,
+    Remove(u32),
+    Velocity {
+        number: u32,
+        velocity: Vec2,
+    },
+}
+
+impl Command {
+    pub fn parse(line: &str) -> Result<Self, String> {
+        let mut args = line.split_whitespace();
+        let Some(name) = args.next() else {
+            return Err("empty command".to_string());
+        };
+        let mut args = Args(args);
+        let command = match name {
+            "help" => Command::Help,
+            "spawn" => {
+                let shape = args.word("shape")?;
+                if shape != "box" {
+                    return Err(format!("unknown shape `{shape}`, expected `box`"));
+                }
+                let position = vec2(args.number("x")?, args.number("y")?);
+                let size = vec2(args.number("width")?, args.number("height")?) / 2.0;
+                let mut body = BodySettings::default();
+                for flag in args.0.by_ref() {
+                    match flag {
+                        "fixed" => body.fixed = true,
+                        "sensor" => body.sensor = true,
+                        other => return Err(format!("unexpected `{other}`")),
+                    }
+                }
+                Command::Spawn {
+                    position,
+                    size,
+                    body,
+                }
+            }
+            "gravity" => Command::Gravity(vec2(args.number("x")?, args.number("y")?)),
+            "timescale" => Command::TimeScale(args.number("scale")?),
+            "physics" => Command::Physics {
+                parameter: args.word("parameter")?.to_string(),
+                value: args.number("value")?,
+            },
+            "clear" => Command::Clear,
+            "select" => Command::Select(args.number("number")?),
+            "joint" => {
+                let kind = match args.word("kind")? {
+                    "fixed" => JointKind::Fixed,
+                    "revolute" => JointKind::Revolute,
+                    other => return Err(format!("unknown joint kind `{other}`")),
+                };
+                Command::Joint {
+                    kind,
+                    a: args.number("first object")?,
+                    b: args.number("second object")?,
+                }
+            }
+            "save" => Command::Save(args.scene()?),
+            "load" => Command::Load(args.scene()?),
+            "list" => Command::List,
+            "remove" => Command::Remove(args.number("number")?),
+            "velocity" => Command::Velocity {
+                number: args.number("number")?,
+                velocity: vec2(args.number("x")?, args.number("y")?),
+            },
+            other => return Err(format!("unknown command `{other}`, try `help`")),
+        };
+        match args.0.next() {
+            Some(extra) => Err(format!("unexpected `{extra}`")),
+            None => Ok(command),
+        }
+    }
+
+    pub fn help() -> String {
+        COMMANDS
+            .iter()
+            .map(|(_, usage)| *usage)
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+struct Args<'a>(std::str::SplitWhitespace<'a>);
+
+impl<'a> Args<'a> {
+    fn word(&mut self, name: &str) -> Result<&'a str, String> {
+        self.0.next().ok_or_else(|| format!("missing {name}"))
+    }
+
+    /// A scene name, `scene` if missing. Scenes stay inside the scenes directory.
+    fn scene(&mut self) -> Result<String, String> {
+        let name = self.0.next().unwrap_or("scene");
+        if name.contains(['/', '\\', ':']) || name.contains("..") {
+            return Err(format!("`{name}` is not a scene name"));
+        }
+        Ok(name.to_string())
+    }
+
+    fn number<T: std::str::FromStr>(&mut self, name: &str) -> Result<T, String> {
+        let word = self.word(name)?;
+        word.parse()
+            .map_err(|_| format!("expected a number for {name}, got `{word}`"))
+    }
+}
+
+/// The developer console, toggled with the console binding.
+pub struct CommandConsole {
+    open: bool,
+    input: String,
+    history: Vec<String>,
+    /// Index into `history` while browsing it with the arrow keys.
+    browsing: Option<usize>,
+    output: VecDeque<String>,
+    /// Focus the input the next time the console is shown.
+    focus: bool,
+}
+
+impl CommandConsole {
+    pub fn new() -> Self {
+        Self {
+            open: false,
+            input: String::new(),
+            history: Vec::new(),
+            browsing: None,
+            output: VecDeque::new(),
+            focus: false,
+        }
+    }
+
+    pub fn toggle(&mut self) {
+        self.open = !self.open;
+        self.focus = self.open;
+    }
+
+    pub fn print(&mut self, text: &str) {
+        print(&mut self.output, text);
+    }
+
+    /// Shows the console and returns a command once one was entered.
+    pub fn show(&mut self, ectx: &egui::Context) -> Option<Command> {
+        if !self.open {
+            return None;
+        }
+        let mut command = None;
+        egui::Window::new("Console")
+            .open(&mut self.open)
+            .default_size([500.0, 300.0])
+            .show(ectx, |ui| {
+                egui::ScrollArea::vertical()
+                    .max_height(250.0)
+                    .auto_shrink([false, true])
+                    .stick_to_bottom(true)
+                    .show(ui, |ui| {
+                        for line in &self.output {
+                            ui.label(egui::RichText::new(line).monospace());
+                        }
+                    });
+                ui.separator();
+                let response = ui.add(
+                    egui::TextEdit::singleline(&mut self.input)
+                        .desired_width(f32::INFINITY)
+                        .font(egui::TextStyle::Monospace)
+                        .lock_focus(true)
+                        .hint_text("help"),
+                );
+                if std::mem::take(&mut self.focus) {
+                    response.request_focus();
+                }
+                if response.has_focus() {
+                    let (tab, up, down) = ui.input(|i| {
+                        (
+                            i.key_pressed(egui::Key::Tab),
+                            i.key_pressed(egui::Key::ArrowUp),
+                            i.key_pressed(egui::Key::ArrowDown),
+                        )
+                    });
+                    if tab {
+                        Self::complete(&mut self.input, &mut self.output);
+                    }
+                    if up || down {
+                        Self::browse(&self.history, &mut self.browsing, &mut self.input, up);
+                    }
+                }
+                if response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
+                    let line = std::mem::take(&mut self.input);
+                    let line = line.trim();
+                    self.browsing = None;
+                    response.request_focus();
+                    if !line.is_empty() {
+                        print(&mut self.output, &format!("> {line}"));
+                        if self.history.last().map(String::as_str) != Some(line) {
+                            self.history.push(line.to_string());
+                        }
+                        match Command::parse(line) {
+                            Ok(parsed) => command = Some(parsed),
+                            Err(e) => print(&mut self.output, &format!("error: {e}")),
+                        }
+                    }
+                }
+                // Usage hints for the command being typed.
+                let word = self.input.split_whitespace().next().unwrap_or("");
+                if !word.is_empty() {
+                    for (name, usage) in COMMANDS {
+                        if name.starts_with(word) {
+                            ui.weak(usage);
+                        }
+                    }
+                }
+            });
+        command
+    }
+
+    /// Completes the command name, or lists the candidates if there are several.
+    fn complete(input: &mut String, output: &mut VecDeque<String>) {
+        if input.contains(' ') {
+            return;
+        }
+        let candidates: Vec<&str> = COMMANDS
+            .iter()
+            .map(|(name, _)| *name)
+            .filter(|name| name.starts_with(input.as_str()))
+            .collect();
+        match candidates.as_slice() {
+            [] => (),
+            [name] => *input = format!("{name} "),
+            names => print(output, &names.join("  ")),
+        }
+    }
+
+    fn browse(history: &[String], browsing: &mut Option<usize>, input: &mut String, up: bool) {
+        if history.is_empty() {
+            return;
+        }
+        *browsing = match (*browsing, up) {
+            (None, true) => Some(history.len() - 1),
+            (None, false) => None,
+            (Some(index), true) => Some(index.saturating_sub(1)),
+            (Some(index), false) if index + 1 < history.len() => Some(index + 1),
+            (Some(_), false) => None,
+        };
+        *input = browsing
+            .map(|index| history[index].clone())
+            .unwrap_or_default();
+    }
+}
+
+fn print(output: &mut VecDeque<String>, text: &str) {
+    for line in text.lines() {
+        if output.len() == OUTPUT_LINES {
+            output.pop_front();
+        }
+        output.push_back(line.to_string());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn error(line: &str) -> String {
+        match Command::parse(line) {
+            Ok(_) => panic!("`{line}` parsed"),
+            Err(e) => e,
+        }
+    }
+
+    #[test]
+    fn scene_names_default_to_scene() {
+        assert!(matches!(Command::parse("save"), Ok(Command::Save(name)) if name == "scene"));
+        assert!(
+            matches!(Command::parse("load level_2"), Ok(Command::Load(name)) if name == "level_2")
+        );
+    }
+
+    #[test]
+    fn scene_names_stay_in_the_scenes_directory() {
+        for name in ["../x", "/x", "a/b", "a\\b", "c:x", ".."] {
+            assert_eq!(
+                error(&format!("save {name}")),
+                format!("`{name}` is not a scene name")
+            );
+            assert!(Command::parse(&format!("load {name}")).is_err());
+        }
+    }
+
+    #[test]
+    fn spawn_takes_full_sizes_and_flags() {
+        let Ok(Command::Spawn {
+            position,
+            size,
+            body,
+        }) = Command::parse("spawn box 1 -2 0.5 3 fixed")
+        else {
+            panic!("spawn did not parse");
+        };
+        assert_eq!(position, vec2(1.0, -2.0));
+        assert_eq!(size, vec2(0.25, 1.5));
+        assert!(body.fixed && !body.sensor);
+        assert_eq!(error("spawn box 0 0 1 1 heavy"), "unexpected `heavy`");
+        assert_eq!(
+            error("spawn circle 0 0 1 1"),
+            "unknown shape `circle`, expected `box`"
+        );
+    }
+
+    #[test]
+    fn physics_takes_a_parameter_and_a_value() {
+        assert!(matches!(
+            Command::parse("physics num_solver_iterations 8"),
+            Ok(Command::Physics { parameter, value }) if parameter == "num_solver_iterations" && value == 8.0
+        ));
+        assert_eq!(error("physics dt"), "missing value");
+    }
+
+    #[test]
+    fn rejects_bad_input() {
+        assert_eq!(error(""), "empty command");
+        assert_eq!(error("jump"), "unknown command `jump`, try `help`");
+        assert_eq!(error("clear now"), "unexpected `now`");
+        assert_eq!(
+            error("select one"),
+            "expected a number for number, got `one`"
+        );
+        assert_eq!(error("remove -1"), "expected a number for number, got `-1`");
+        assert_eq!(error("joint rope 1 2"), "unknown joint kind `rope`");
+    }
+
+    #[test]
+    fn every_command_has_usage() {
+        for (name, usage) in COMMANDS {
+            assert!(usage.starts_with(name));
+        }
+    }
+}
@@ -0,0 +1,166 @@
+//! Conveyor belts: fixed boxes whose surface carries resting bodies along.
+//!
+//! Belts are spawned objects like the platform. Every tick [`apply`] pulls the velocity
+//! of bodies in contact with a belt towards the belt's surface speed and scrolls its
+//! texture. The contacts come from the collision events the collision log drains.
+
+use std::collections::HashSet;
+
+use let_engine::prelude::{
+    gpu::{
+        VulkanTypes, buffer::BufferId, material::MaterialId, model::ModelId, texture::TextureId,
+    },
+    *,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    Ctx,
+    error::{ObjectLookup, Result, SandboxError},
+    sandbox::Sandbox,
+};
+
+/// The settings of a belt, as saved in scenes.
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Belt {
+    /// Surface speed along the belt's x axis. Negative runs the other way.
+    pub speed: f32,
+    /// Fraction of the difference to the belt speed removed from touching bodies every
+    /// sixtieth of a second, whatever the tick rate.
+    pub grip: f32,
+}
+
+impl Default for Belt {
+    fn default() -> Self {
+        Self {
+            speed: 0.3,
+            grip: 0.2,
+        }
+    }
+}
+
+/// A spawned belt.
+pub struct Conveyor {
+    pub belt: Belt,
+    /// Half extents.
+    size: Vec2,
+    model: ModelId<TVert>,
+    /// How far the texture has scrolled, in texture coordinates.
+    scroll: f32,
+    /// Objects in contact with the belt.
+    contacts: HashSet<ObjectId>,
+}
+
+impl Conveyor {
+    pub fn new(belt: Belt, size: Vec2, model: ModelId<TVert>) -> Self {
+        Self {
+            belt,
+            size,
+            model,
+            scroll: 0.0,
+            contacts: HashSet::new(),
+        }
+    }
+
+    pub fn model(&self) -> ModelId<TVert> {
+        self.model
+    }
+
+    /// Records that `other` started or stopped touching the belt.
+    pub fn set_contact(&mut self, other: ObjectId, touching: bool) {
+        if touching {
+            self.contacts.insert(other);
+        } else {
+            self.contacts.remove(&other);
+        }
+    }
+}
+
+/// The unit box of a belt. The texture repeats along it in squares as tall as the belt.
+fn vertices(size: Vec2, scroll: f32) -> Vec<TVert> {
+    let u = size.x / size.y;
+    let corner = |x: f32, y: f32| tvert(x, y, x * u - scroll, y);
+    vec![
+        corner(1.0, 1.0),
+        corner(1.0, -1.0),
+        corner(-1.0, 1.0),
+        corner(-1.0, 1.0),
+        corner(1.0, -1.0),
+        corner(-1.0, -1.0),
+    ]
+}
+
+/// What belts are drawn with.
+pub struct ConveyorStyle {
+    pub material: MaterialId<TVert>,
+    pub texture: TextureId,
+    pub color: BufferId<Color>,
+}
+
+impl ConveyorStyle {
+    /// Loads a box model for a belt with the given half extents and an appearance drawing it.
+    pub fn belt(&self, ctx: &Ctx, size: Vec2) -> Result<(ModelId<TVert>, Appearance<VulkanTypes>)> {
+        let model = ctx
+            .gpu
+            .load_model(&model!(vertices(size, 0.0), vec![0, 1, 2, 3, 4, 5]))
+            .map_err(SandboxError::gpu)?;
+        Ok((model, self.appearance(ctx, model)?))
+    }
+
+    /// An appearance drawing a loaded belt model.
+    pub fn appearance(&self, ctx: &Ctx, model: ModelId<TVert>) -> Result<Appearance<VulkanTypes>> {
+        AppearanceBuilder::default()
+            .model(model)
+            .material(self.material)
+            .descriptors(&[
+                (Location::new(0, 0), Descriptor::Mvp),
+                (Location::new(1, 0), Descriptor::buffer(self.color)),
+                (Location::new(2, 0), Descriptor::Texture(self.texture)),
+            ])
+            .build(&ctx.gpu)
+            .map_err(SandboxError::gpu)
+    }
+}
+
+/// Carries the bodies touching a belt along and scrolls the belt textures.
+///
+/// Like a real belt, the underside runs the opposite way.
+pub fn apply(ctx: &Ctx, sandbox: &mut Sandbox, dt: f32) -> Result<()> {
+    for (&id, conveyor) in &sandbox.conveyors {
+        let object = ctx.scene.object(id).or_stale(id)?;
+        let (position, rotation) = (object.transform.position, object.transform.rotation);
+        let belt = conveyor.belt;
+        let tangent = Vec2::from_angle(rotation);
+        let grip = 1.0 - (1.0 - belt.grip).powf(dt * 60.0);
+
+        for &other in &conveyor.contacts {
+            let object = ctx.scene.object_mut(other).or_stale(other)?;
+            // The y axis points down, so the side above the belt is against its normal.
+            let above = tangent.perp().dot(object.transform.position - position) <= 0.0;
+            let Some(body) = object.rigid_body_mut() else {
+                continue;
+            };
+            if !body.is_dynamic() {
+                continue;
+            }
+            let target = if above { belt.speed } else { -belt.speed };
+            let velocity = vec2(body.linvel().x, body.linvel().y).dot(tangent);
+            let impulse = tangent * (target - velocity) * grip * body.mass();
+            body.apply_impulse(impulse.into(), true);
+        }
+    }
+
+    for conveyor in sandbox.conveyors.values_mut() {
+        // One unit of texture coordinates spans half the belt height. Wrapping at two
+        // keeps whole repeats of the texture either way it is mapped.
+        conveyor.scroll =
+            (conveyor.scroll + conveyor.belt.speed * dt / conveyor.size.y).rem_euclid(2.0);
+        let belt = vertices(conveyor.size, conveyor.scroll);
+        ctx.gpu
+            .model(conveyor.model)
+            .map_err(SandboxError::gpu)?
+            .write_vertices(|buffer| buffer.copy_from_slice(&belt), belt.len())
+            .map_err(SandboxError::gpu)?;
+    }
+    Ok(())
+}
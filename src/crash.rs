@@ -0,0 +1,217 @@
+use std::{
+    any::Any,
+    backtrace::Backtrace,
+    collections::VecDeque,
+    fmt::Debug,
+    fs,
+    panic::{self, PanicHookInfo},
+    path::PathBuf,
+    sync::{Mutex, PoisonError},
+    time::{Instant, SystemTime, UNIX_EPOCH},
+};
+
+use serde::Serialize;
+
+use crate::{physics::PhysicsSettings, sandbox::Snapshot};
+
+/// How many input events are kept for the crash report.
+const INPUT_HISTORY: usize = 64;
+
+/// The panic caught by the hook, until the unwind boundary picks it up.
+static LAST_PANIC: Mutex<Option<Panic>> = Mutex::new(None);
+
+/// A panic message with where it happened.
+pub struct Panic {
+    message: String,
+    location: Option<String>,
+    backtrace: String,
+}
+
+/// Takes the panic recorded by the hook, falling back to the unwind payload.
+pub fn take_panic(payload: &(dyn Any + Send)) -> Panic {
+    LAST_PANIC
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .take()
+        .unwrap_or_else(|| Panic {
+            message: payload_message(payload),
+            location: None,
+            backtrace: "not captured".to_string(),
+        })
+}
+
+/// Records panics for the crash report, then runs the default hook.
+pub fn install_hook() {
+    let default = panic::take_hook();
+    panic::set_hook(Box::new(move |info: &PanicHookInfo| {
+        let panic = Panic {
+            message: payload_message(info.payload()),
+            location: info.location().map(|location| location.to_string()),
+            backtrace: Backtrace::force_capture().to_string(),
+        };
+        log::error!(
+            "Panic at {}: {}",
+            panic.location.as_deref().unwrap_or("unknown location"),
+            panic.message
+        );
+        *LAST_PANIC.lock().unwrap_or_else(PoisonError::into_inner) = Some(panic);
+        default(info);
+    }));
+}
+
+fn payload_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "unknown panic payload".to_string()
+    }
+}
+
+/// The last input and window events, oldest first.
+pub struct InputLog {
+    started: Instant,
+    events: VecDeque<InputRecord>,
+}
+
+#[derive(Clone, Serialize)]
+pub struct InputRecord {
+    /// Seconds since start.
+    time: f32,
+    event: String,
+}
+
+impl InputLog {
+    pub fn new() -> Self {
+        Self {
+            started: Instant::now(),
+            events: VecDeque::with_capacity(INPUT_HISTORY),
+        }
+    }
+
+    pub fn record(&mut self, event: &impl Debug) {
+        if self.events.len() == INPUT_HISTORY {
+            self.events.pop_front();
+        }
+        self.events.push_back(InputRecord {
+            time: self.started.elapsed().as_secs_f32(),
+            event: format!("{event:?}"),
+        });
+    }
+}
+
+/// Engine and sandbox settings at the time of the crash.
+#[derive(Serialize)]
+pub struct Settings {
+    pub physics: PhysicsSettings,
+    pub time_scale: f32,
+    pub fps_cap: f64,
+    pub camera_position: [f32; 2],
+    pub camera_size: [f32; 2],
+    pub tool: &'static str,
+}
+
+/// A soft body, which the scene snapshot cannot hold.
+#[derive(Serialize)]
+pub struct OmittedSoftBody {
+    pub particles: usize,
+    /// Average position of the particles.
+    pub center: [f32; 2],
+}
+
+#[derive(Serialize)]
+struct Report<'a> {
+    version: &'static str,
+    callback: &'static str,
+    message: &'a str,
+    location: Option<&'a str>,
+    backtrace: &'a str,
+    settings: Settings,
+    input: Vec<InputRecord>,
+    /// Missing if the scene could not be read anymore.
+    snapshot: Option<Snapshot>,
+    /// Soft bodies left out of the snapshot.
+    soft_bodies: Vec<OmittedSoftBody>,
+}
+
+/// A caught crash, shown in the crash dialog.
+pub struct Crash {
+    pub callback: &'static str,
+    pub message: String,
+    /// The written report, or why writing it failed.
+    pub report: Result<PathBuf, String>,
+}
+
+impl Crash {
+    /// Builds the crash report for a panic caught in `callback` and writes it to
+    /// `crash-<unix time>.json` in the working directory.
+    pub fn new(
+        callback: &'static str,
+        panic: Panic,
+        settings: Settings,
+        input: &InputLog,
+        snapshot: Option<(Snapshot, Vec<OmittedSoftBody>)>,
+    ) -> Self {
+        let (snapshot, soft_bodies) = snapshot.unzip();
+        let report = Report {
+            version: env!("CARGO_PKG_VERSION"),
+            callback,
+            message: &panic.message,
+            location: panic.location.as_deref(),
+            backtrace: &panic.backtrace,
+            settings,
+            input: input.events.iter().cloned().collect(),
+            snapshot,
+            soft_bodies: soft_bodies.unwrap_or_default(),
+        };
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        let path = PathBuf::from(format!("crash-{timestamp}.json"));
+        let report = serde_json::to_string_pretty(&report)
+            .map_err(|e| e.to_string())
+            .and_then(|report| fs::write(&path, report).map_err(|e| e.to_string()))
+            .map(|()| path);
+        Self {
+            callback,
+            message: panic.message,
+            report,
+        }
+    }
+
+    /// Shows the crash dialog and returns the button the user clicked.
+    pub fn ui(&self, ectx: &egui::Context) -> CrashAction {
+        let mut action = CrashAction::None;
+        egui::Window::new("Crash")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::vec2(0.0, 0.0))
+            .show(ectx, |ui| {
+                ui.label(format!("The sandbox panicked in `{}`:", self.callback));
+                ui.label(egui::RichText::new(&self.message).monospace());
+                ui.separator();
+                match &self.report {
+                    Ok(path) => ui.label(format!("Crash report written to {}", path.display())),
+                    Err(e) => ui.label(format!("Failed to write crash report: {e}")),
+                };
+                ui.label("Time is paused. The scene may be in an inconsistent state.");
+                ui.horizontal(|ui| {
+                    if ui.button("Continue").clicked() {
+                        action = CrashAction::Continue;
+                    }
+                    if ui.button("Quit").clicked() {
+                        action = CrashAction::Quit;
+                    }
+                });
+            });
+        action
+    }
+}
+
+pub enum CrashAction {
+    None,
+    Continue,
+    Quit,
+}
@@ -0,0 +1,107 @@
+use std::fmt::{self, Debug, Display};
+
+use let_engine::prelude::ObjectId;
+
+/// Errors raised by the sandbox.
+///
+/// Engine errors are kept as their debug messages, so their exact types do not leak into
+/// every signature here.
+#[derive(Debug)]
+pub enum SandboxError {
+    /// An asset could not be found or decoded.
+    Asset {
+        path: String,
+        message: String,
+    },
+    /// A GPU resource could not be created or written.
+    Gpu(String),
+    /// A scene operation like adding an object or joint failed.
+    Scene(String),
+    /// A label could not be created or updated.
+    Label(String),
+    /// An object was used after it had been removed.
+    StaleObject(ObjectId),
+    /// No window is available.
+    NoWindow,
+    Io(std::io::Error),
+    Json(serde_json::Error),
+    /// A script failed to compile or run.
+    Script(String),
+    /// Sound could not be played.
+    Audio(String),
+}
+
+impl SandboxError {
+    pub fn asset<E: Debug>(path: &str) -> impl FnOnce(E) -> Self + '_ {
+        move |e| Self::Asset {
+            path: path.to_string(),
+            message: format!("{e:?}"),
+        }
+    }
+
+    pub fn gpu(e: impl Debug) -> Self {
+        Self::Gpu(format!("{e:?}"))
+    }
+
+    pub fn scene(e: impl Debug) -> Self {
+        Self::Scene(format!("{e:?}"))
+    }
+
+    pub fn label(e: impl Debug) -> Self {
+        Self::Label(format!("{e:?}"))
+    }
+
+    pub fn audio(e: impl Debug) -> Self {
+        Self::Audio(format!("{e:?}"))
+    }
+}
+
+impl Display for SandboxError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SandboxError::Asset { path, message } => write!(f, "asset {path:?}: {message}"),
+            SandboxError::Gpu(message) => write!(f, "gpu: {message}"),
+            SandboxError::Scene(message) => write!(f, "scene: {message}"),
+            SandboxError::Label(message) => write!(f, "label: {message}"),
+            SandboxError::StaleObject(id) => write!(f, "object {id:?} no longer exists"),
+            SandboxError::NoWindow => write!(f, "no window available"),
+            SandboxError::Io(e) => write!(f, "io: {e}"),
+            SandboxError::Json(e) => write!(f, "json: {e}"),
+            SandboxError::Script(message) => write!(f, "script: {message}"),
+            SandboxError::Audio(message) => write!(f, "audio: {message}"),
+        }
+    }
+}
+
+impl std::error::Error for SandboxError {}
+
+impl From<std::io::Error> for SandboxError {
+    fn from(e: std::io::Error) -> Self {
+        Self::Io(e)
+    }
+}
+
+impl From<serde_json::Error> for SandboxError {
+    fn from(e: serde_json::Error) -> Self {
+        Self::Json(e)
+    }
+}
+
+pub type Result<T, E = SandboxError> = std::result::Result<T, E>;
+
+/// Turns a failed object lookup into [`SandboxError::StaleObject`].
+pub trait ObjectLookup<T> {
+    fn or_stale(self, id: ObjectId) -> Result<T>;
+}
+
+impl<T> ObjectLookup<T> for Option<T> {
+    fn or_stale(self, id: ObjectId) -> Result<T> {
+        self.ok_or(SandboxError::StaleObject(id))
+    }
+}
+
+impl<T, E> ObjectLookup<T> for Result<T, E> {
+    fn or_stale(self, id: ObjectId) -> Result<T> {
+        self.map_err(|_| SandboxError::StaleObject(id))
+    }
+}
@@ -0,0 +1,272 @@
+//! Force fields: wind zones, attractors and point gravity sources.
+//!
+//! Fields are spawned objects with a sensor collider, so the tools can select, move and
+//! delete them like boxes. Their forces are applied every tick by [`apply`].
+
+use let_engine::prelude::{gpu::VulkanTypes, *};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    Ctx, angle_between,
+    error::{ObjectLookup, Result},
+    sandbox::Sandbox,
+};
+
+/// Distance below which point gravity stops growing, so bodies at the center do not
+/// get launched.
+const MIN_DISTANCE: f32 = 0.05;
+
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FieldKind {
+    /// A constant force on every body inside a rectangle.
+    Wind { force: [f32; 2] },
+    /// Pulls bodies towards the center, fading out towards the radius.
+    /// A negative strength pushes them away.
+    Attractor { strength: f32 },
+    /// Gravity towards a point, falling off with the squared distance.
+    /// Unlike the other fields this accelerates all bodies equally, regardless of mass.
+    Planet { gravity: f32 },
+}
+
+impl FieldKind {
+    pub const DEFAULTS: [FieldKind; 3] = [
+        FieldKind::Wind { force: [0.02, 0.0] },
+        FieldKind::Attractor { strength: 0.02 },
+        FieldKind::Planet { gravity: 0.5 },
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            FieldKind::Wind { .. } => "Wind",
+            FieldKind::Attractor { .. } => "Attractor",
+            FieldKind::Planet { .. } => "Planet",
+        }
+    }
+
+    /// Whether the field is a circle rather than a rectangle.
+    pub fn radial(self) -> bool {
+        !matches!(self, FieldKind::Wind { .. })
+    }
+
+    /// The force on a body at `offset` from the field's center, given the field's
+    /// half extents and the body's mass.
+    fn force(self, offset: Vec2, size: Vec2, mass: f32) -> Vec2 {
+        match self {
+            FieldKind::Wind { force } => Vec2::from_array(force),
+            FieldKind::Attractor { strength } => {
+                let falloff = (1.0 - offset.length() / size.x).max(0.0);
+                -offset.normalize_or_zero() * strength * falloff
+            }
+            FieldKind::Planet { gravity } => {
+                let distance = offset.length().max(MIN_DISTANCE);
+                -offset.normalize_or_zero() * gravity * mass / (distance * distance)
+            }
+        }
+    }
+
+    /// Arrow shown on the field, relative to its center and unrotated.
+    fn arrow(self, size: Vec2) -> (Vec2, Vec2) {
+        match self {
+            FieldKind::Wind { force } => {
+                let direction = Vec2::from_array(force).normalize_or(Vec2::X);
+                (-direction * size * 0.8, direction * size * 0.8)
+            }
+            FieldKind::Attractor { strength } if strength < 0.0 => {
+                (Vec2::ZERO, vec2(size.x * 0.8, 0.0))
+            }
+            FieldKind::Attractor { .. } | FieldKind::Planet { .. } => {
+                (vec2(size.x * 0.8, 0.0), Vec2::ZERO)
+            }
+        }
+    }
+}
+
+/// A field and the arrow object drawn on top of it.
+pub struct Field {
+    pub kind: FieldKind,
+    pub arrow: ObjectId,
+}
+
+/// Translucent appearances of the field zones and their arrows.
+pub struct FieldStyle {
+    pub wind: Appearance<VulkanTypes>,
+    pub attractor: Appearance<VulkanTypes>,
+    pub planet: Appearance<VulkanTypes>,
+    /// A line strip arrow one unit long, pointing along x.
+    pub arrow: Appearance<VulkanTypes>,
+}
+
+impl FieldStyle {
+    pub fn zone(&self, kind: FieldKind) -> &Appearance<VulkanTypes> {
+        match kind {
+            FieldKind::Wind { .. } => &self.wind,
+            FieldKind::Attractor { .. } => &self.attractor,
+            FieldKind::Planet { .. } => &self.planet,
+        }
+    }
+}
+
+/// The sensor collider of a field with the given half extents.
+pub fn collider(kind: FieldKind, size: Vec2, groups: InteractionGroups) -> Collider {
+    let builder = if kind.radial() {
+        ColliderBuilder::circle(size.x)
+    } else {
+        ColliderBuilder::square(size.x, size.y)
+    };
+    builder.sensor(true).collision_groups(groups).build()
+}
+
+/// Pushes the bodies inside every field and moves the arrows along with their fields.
+pub fn apply(ctx: &Ctx, sandbox: &Sandbox, dt: f32) -> Result<()> {
+    for (&id, field) in &sandbox.fields {
+        let object = ctx.scene.object(id).or_stale(id)?;
+        let (position, rotation) = (object.transform.position, object.transform.rotation);
+        let size = object.appearance.transform().size;
+
+        let shape = if field.kind.radial() {
+            Shape::circle(size.x)
+        } else {
+            Shape::square(size.x, size.y)
+        };
+        for other in sandbox.objects_in_shape(ctx, shape, (position, rotation)) {
+            if sandbox.fields.contains_key(&other) {
+                continue;
+            }
+            let object = ctx.scene.object_mut(other).or_stale(other)?;
+            let offset = object.transform.position - position;
+            let Some(body) = object.rigid_body_mut() else {
+                continue;
+            };
+            if !body.is_dynamic() {
+                continue;
+            }
+            let mut force = field.kind.force(offset, size, body.mass());
+            if !field.kind.radial() {
+                force = Vec2::from_angle(rotation).rotate(force);
+            }
+            body.apply_impulse((force * dt).into(), true);
+        }
+
+        let (from, to) = field.kind.arrow(size);
+        let (from, to) = (
+            position + Vec2::from_angle(rotation).rotate(from),
+            position + Vec2::from_angle(rotation).rotate(to),
+        );
+        let arrow = ctx.scene.object_mut(field.arrow).or_stale(field.arrow)?;
+        arrow.transform.position = from;
+        arrow.transform.rotation = angle_between(from, to);
+        arrow.appearance.transform_mut().size = Vec2::splat(from.distance(to));
+    }
+    Ok(())
+}
+
+/// The fields window: every field, and an editor for the selected one.
+pub fn ui(ui: &mut egui::Ui, ctx: &Ctx, sandbox: &mut Sandbox) -> Result<()> {
+    let mut fields: Vec<(u32, ObjectId, FieldKind)> = sandbox
+        .fields
+        .iter()
+        .map(|(&id, field)| (sandbox.number(id).unwrap_or_default(), id, field.kind))
+        .collect();
+    fields.sort_by_key(|(number, _, _)| *number);
+    if fields.is_empty() {
+        ui.label("No fields. Place them with the field tool.");
+    }
+    for (number, id, kind) in fields {
+        let selected = sandbox.selected_object == Some(id);
+        if ui
+            .selectable_label(selected, format!("#{number} {}", kind.name()))
+            .clicked()
+        {
+            sandbox.selected_object = Some(id);
+        }
+    }
+
+    let Some(id) = sandbox.selected_object else {
+        return Ok(());
+    };
+    let groups = sandbox.groups.interaction_groups(sandbox.group(id));
+    let Some(field) = sandbox.fields.get_mut(&id) else {
+        return Ok(());
+    };
+    ui.separator();
+    let object = ctx.scene.object_mut(id).or_stale(id)?;
+    let mut position = object.transform.position;
+    let mut rotation = object.transform.rotation.to_degrees();
+    let mut size = object.appearance.transform().size;
+    let mut kind = field.kind;
+
+    egui::Grid::new("field").show(ui, |ui| {
+        ui.label("Position");
+        ui.horizontal(|ui| {
+            ui.add(egui::DragValue::new(&mut position.x).speed(0.01));
+            ui.add(egui::DragValue::new(&mut position.y).speed(0.01));
+        });
+        ui.end_row();
+        match &mut kind {
+            FieldKind::Wind { force } => {
+                ui.label("Size");
+                ui.horizontal(|ui| {
+                    ui.add(
+                        egui::DragValue::new(&mut size.x)
+                            .speed(0.01)
+                            .range(0.01..=50.0),
+                    );
+                    ui.add(
+                        egui::DragValue::new(&mut size.y)
+                            .speed(0.01)
+                            .range(0.01..=50.0),
+                    );
+                });
+                ui.end_row();
+                ui.label("Rotation");
+                ui.add(egui::DragValue::new(&mut rotation).suffix("°"));
+                ui.end_row();
+                ui.label("Force");
+                ui.horizontal(|ui| {
+                    ui.add(egui::DragValue::new(&mut force[0]).speed(0.001));
+                    ui.add(egui::DragValue::new(&mut force[1]).speed(0.001));
+                });
+            }
+            FieldKind::Attractor { strength } => {
+                ui.label("Radius");
+                ui.add(
+                    egui::DragValue::new(&mut size.x)
+                        .speed(0.01)
+                        .range(0.01..=50.0),
+                );
+                ui.end_row();
+                ui.label("Strength");
+                ui.add(egui::DragValue::new(strength).speed(0.001));
+            }
+            FieldKind::Planet { gravity } => {
+                ui.label("Radius");
+                ui.add(
+                    egui::DragValue::new(&mut size.x)
+                        .speed(0.01)
+                        .range(0.01..=50.0),
+                );
+                ui.end_row();
+                ui.label("Gravity");
+                ui.add(egui::DragValue::new(gravity).speed(0.01));
+            }
+        }
+        ui.end_row();
+    });
+    if kind.radial() {
+        size.y = size.x;
+    }
+
+    if position != object.transform.position {
+        object.transform.position = position;
+    }
+    if rotation != object.transform.rotation.to_degrees() {
+        object.transform.rotation = rotation.to_radians();
+    }
+    if size != object.appearance.transform().size {
+        object.appearance.transform_mut().size = size;
+        object.set_collider(Some(collider(kind, size, groups)));
+    }
+    field.kind = kind;
+    Ok(())
+}
@@ -0,0 +1,204 @@
+use std::{collections::HashSet, time::Instant};
+
+use gilrs::{Axis, Button, EventType, GamepadId, Gilrs};
+use let_engine::prelude::*;
+
+use crate::{Ctx, bindings::Action};
+
+/// Buttons held down as actions, mirroring the default mouse bindings.
+const HELD: [(Button, Action); 2] = [
+    (Button::South, Action::Primary),
+    (Button::East, Action::Secondary),
+];
+
+/// Buttons that trigger an action once when pressed.
+const PRESSED: [(Button, Action); 2] = [
+    (Button::West, Action::NextTool),
+    (Button::Start, Action::Pause),
+];
+
+/// Gamepad input driving a virtual cursor, the camera and the tool actions.
+pub struct GamepadInput {
+    gilrs: Option<Gilrs>,
+    active: Option<GamepadId>,
+    /// Virtual cursor position in world space.
+    cursor: Vec2,
+    /// Whether the virtual cursor replaces the mouse cursor.
+    using: bool,
+    last_mouse: Vec2,
+    last_update: Instant,
+    held: HashSet<Action>,
+    pressed: Vec<Action>,
+    /// Cursor speed in pixels per second at full stick deflection.
+    pub cursor_speed: f32,
+    pub pan_speed: f32,
+    pub zoom_speed: f32,
+    pub deadzone: f32,
+    status: String,
+}
+
+impl GamepadInput {
+    pub fn new() -> Self {
+        let (gilrs, status) = match Gilrs::new() {
+            Ok(gilrs) => (Some(gilrs), String::new()),
+            Err(e) => (None, format!("Gamepad support unavailable: {e}")),
+        };
+        let active = gilrs
+            .as_ref()
+            .and_then(|gilrs| gilrs.gamepads().next().map(|(id, _)| id));
+        Self {
+            gilrs,
+            active,
+            cursor: Vec2::ZERO,
+            using: false,
+            last_mouse: Vec2::ZERO,
+            last_update: Instant::now(),
+            held: HashSet::new(),
+            pressed: Vec::new(),
+            cursor_speed: 800.0,
+            pan_speed: 800.0,
+            zoom_speed: 1.5,
+            deadzone: 0.15,
+            status,
+        }
+    }
+
+    /// Whether gamepad support could be initialized.
+    pub fn available(&self) -> bool {
+        self.gilrs.is_some()
+    }
+
+    /// Actions whose buttons are currently held.
+    pub fn held(&self) -> &HashSet<Action> {
+        &self.held
+    }
+
+    /// Takes the actions triggered by button presses since the last call.
+    pub fn take_pressed(&mut self) -> Vec<Action> {
+        std::mem::take(&mut self.pressed)
+    }
+
+    /// Whether the virtual cursor is in use instead of the mouse.
+    pub fn using(&self) -> bool {
+        self.using
+    }
+
+    pub fn cursor(&self) -> Vec2 {
+        self.cursor
+    }
+
+    /// Polls gamepad events, moves the virtual cursor and pans and zooms the camera.
+    pub fn update(&mut self, ctx: &Ctx) {
+        let delta = self.last_update.elapsed().as_secs_f32();
+        self.last_update = Instant::now();
+
+        // Moving the mouse hands control back to it.
+        let mouse = ctx.input.scaled_cursor(CameraScaling::Expand);
+        if mouse != self.last_mouse {
+            self.last_mouse = mouse;
+            self.using = false;
+        }
+
+        let Some(gilrs) = &mut self.gilrs else {
+            return;
+        };
+        while let Some(event) = gilrs.next_event() {
+            match event.event {
+                EventType::Connected => {
+                    log::info!("Gamepad connected: {}", gilrs.gamepad(event.id).name());
+                    self.active.get_or_insert(event.id);
+                }
+                EventType::Disconnected if self.active == Some(event.id) => {
+                    log::info!("Active gamepad disconnected");
+                    self.active = gilrs.gamepads().next().map(|(id, _)| id);
+                    self.held.clear();
+                }
+                EventType::ButtonPressed(button, _) => {
+                    self.active = Some(event.id);
+                    if let Some((_, action)) = PRESSED.iter().find(|(b, _)| *b == button) {
+                        self.pressed.push(*action);
+                    }
+                }
+                _ => (),
+            }
+        }
+
+        let Some(gamepad) = self.active.map(|id| gilrs.gamepad(id)) else {
+            self.using = false;
+            self.held.clear();
+            return;
+        };
+
+        let deadzone = |value: f32| {
+            if value.abs() < self.deadzone {
+                0.0
+            } else {
+                value
+            }
+        };
+        let left = vec2(
+            deadzone(gamepad.value(Axis::LeftStickX)),
+            -deadzone(gamepad.value(Axis::LeftStickY)),
+        );
+        let right = vec2(
+            deadzone(gamepad.value(Axis::RightStickX)),
+            -deadzone(gamepad.value(Axis::RightStickY)),
+        );
+        let trigger = |button| {
+            gamepad
+                .button_data(button)
+                .map(|data| data.value())
+                .unwrap_or(0.0)
+        };
+        let zoom = trigger(Button::LeftTrigger2) - trigger(Button::RightTrigger2);
+
+        self.held = HELD
+            .iter()
+            .filter(|(button, _)| gamepad.is_pressed(*button))
+            .map(|(_, action)| *action)
+            .collect();
+
+        if !self.using && (left != Vec2::ZERO || !self.held.is_empty()) {
+            self.using = true;
+            self.cursor = ctx.input.cursor_to_world(ctx.scene.root_view());
+        }
+
+        let camera = ctx.scene.root_view_mut().camera_mut();
+        let pan = right * self.pan_speed * delta * camera.size;
+        camera.position += pan;
+        camera.size = camera.size + camera.size * zoom * self.zoom_speed * delta;
+        self.cursor += pan + left * self.cursor_speed * delta * camera.size;
+    }
+
+    pub fn ui(&mut self, ui: &mut egui::Ui) {
+        match &self.gilrs {
+            Some(gilrs) => {
+                let mut connected = false;
+                for (id, gamepad) in gilrs.gamepads() {
+                    connected = true;
+                    let active = self.active == Some(id);
+                    if ui
+                        .selectable_label(active, format!("{} ({id})", gamepad.name()))
+                        .clicked()
+                    {
+                        self.active = Some(id);
+                    }
+                }
+                if !connected {
+                    ui.label("No gamepad connected");
+                }
+            }
+            None => {
+                ui.label(&self.status);
+            }
+        }
+        ui.separator();
+        ui.add(egui::Slider::new(&mut self.cursor_speed, 100.0..=3000.0).text("Cursor speed"));
+        ui.add(egui::Slider::new(&mut self.pan_speed, 100.0..=3000.0).text("Pan speed"));
+        ui.add(egui::Slider::new(&mut self.zoom_speed, 0.1..=5.0).text("Zoom speed"));
+        ui.add(egui::Slider::new(&mut self.deadzone, 0.0..=0.5).text("Dead zone"));
+        ui.separator();
+        ui.label("Left stick: cursor\nRight stick: pan\nTriggers: zoom");
+        ui.label("A: use tool\nB: secondary tool action\nX: next tool\nStart: pause");
+    }
+}
@@ -0,0 +1,210 @@
+//! Collision groups: named sets of objects and which of them collide with each other.
+//!
+//! Every spawned object belongs to exactly one group, group 0 unless assigned otherwise.
+//! Boxes are tinted in their group's color.
+
+use let_engine::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{Ctx, error::Result, sandbox::Sandbox};
+
+/// Rapier supports 32 groups, the sandbox offers the first 16.
+pub const GROUP_COUNT: usize = 16;
+
+/// The tint of a group. Group 0 keeps the default box color.
+pub fn color(group: usize) -> [f32; 4] {
+    if group == 0 {
+        return [0.7, 0.3, 0.3, 1.0];
+    }
+    let hue = (group - 1) as f32 / (GROUP_COUNT - 1) as f32;
+    egui::ecolor::Hsva::new(hue, 0.6, 0.95, 1.0).to_rgba_unmultiplied()
+}
+
+fn color32(group: usize) -> egui::Color32 {
+    let [r, g, b, a] = color(group);
+    egui::Rgba::from_rgba_unmultiplied(r, g, b, a).into()
+}
+
+/// Group names and the filter matrix, saved with scenes.
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GroupTable {
+    /// How many groups the editor shows.
+    pub count: usize,
+    pub names: Vec<String>,
+    /// Bit `j` of entry `i` is set when group `i` collides with group `j`.
+    /// Kept symmetric by [`GroupTable::set_collides`].
+    pub filters: Vec<u16>,
+}
+
+impl Default for GroupTable {
+    fn default() -> Self {
+        let mut names = vec!["Default".to_string()];
+        names.extend((1..GROUP_COUNT).map(|group| format!("Group {group}")));
+        Self {
+            count: 4,
+            names,
+            filters: vec![u16::MAX; GROUP_COUNT],
+        }
+    }
+}
+
+impl GroupTable {
+    pub fn name(&self, group: usize) -> &str {
+        self.names.get(group).map_or("?", String::as_str)
+    }
+
+    pub fn collides(&self, a: usize, b: usize) -> bool {
+        self.filters
+            .get(a)
+            .is_some_and(|filter| filter & 1 << b != 0)
+    }
+
+    pub fn set_collides(&mut self, a: usize, b: usize, collides: bool) {
+        for (row, column) in [(a, b), (b, a)] {
+            if collides {
+                self.filters[row] |= 1 << column;
+            } else {
+                self.filters[row] &= !(1 << column);
+            }
+        }
+    }
+
+    /// The interaction groups of a collider in `group`.
+    pub fn interaction_groups(&self, group: usize) -> InteractionGroups {
+        let filter = self.filters.get(group).copied().unwrap_or(u16::MAX);
+        InteractionGroups::new(
+            Group::from_bits_truncate(1 << group),
+            Group::from_bits_truncate(filter as u32),
+        )
+    }
+
+    /// Fills in names and filters missing from an older or hand edited file.
+    pub fn normalize(&mut self) {
+        self.count = self.count.clamp(1, GROUP_COUNT);
+        for group in self.names.len()..GROUP_COUNT {
+            self.names.push(format!("Group {group}"));
+        }
+        self.names.truncate(GROUP_COUNT);
+        self.filters.resize(GROUP_COUNT, u16::MAX);
+    }
+}
+
+/// A combo box picking one of the groups in use.
+pub fn picker(ui: &mut egui::Ui, id: &str, table: &GroupTable, group: &mut usize) {
+    egui::ComboBox::from_id_salt(id)
+        .selected_text(egui::RichText::new(table.name(*group)).color(color32(*group)))
+        .show_ui(ui, |ui| {
+            for index in 0..table.count {
+                let text = egui::RichText::new(table.name(index)).color(color32(index));
+                ui.selectable_value(group, index, text);
+            }
+        });
+}
+
+/// The groups window: the selected object's group, the group names and the filter matrix.
+pub fn ui(ui: &mut egui::Ui, ctx: &Ctx, sandbox: &mut Sandbox) -> Result<()> {
+    if let Some(id) = sandbox.selected_object {
+        let number = sandbox.number(id).unwrap_or_default();
+        let mut group = sandbox.group(id);
+        ui.horizontal(|ui| {
+            ui.label(format!("Object #{number} is in"));
+            picker(ui, "selected_group", &sandbox.groups, &mut group);
+        });
+        if group != sandbox.group(id) {
+            sandbox.set_group(ctx, id, group)?;
+        }
+    } else {
+        ui.label("Select an object to change its group.");
+    }
+    ui.separator();
+
+    let mut table = sandbox.groups.clone();
+    ui.horizontal(|ui| {
+        ui.add(egui::Slider::new(&mut table.count, 1..=GROUP_COUNT).text("Groups"));
+        if ui.button("Collide all").clicked() {
+            table.filters = vec![u16::MAX; GROUP_COUNT];
+        }
+    });
+
+    egui::Grid::new("group_matrix").show(ui, |ui| {
+        ui.label("");
+        ui.label("");
+        // Column headers are the group numbers, names are too wide.
+        for column in 0..table.count {
+            ui.label(egui::RichText::new(column.to_string()).color(color32(column)));
+        }
+        ui.end_row();
+        for row in 0..table.count {
+            ui.label(egui::RichText::new(row.to_string()).color(color32(row)));
+            ui.add(egui::TextEdit::singleline(&mut table.names[row]).desired_width(100.0));
+            // The matrix is symmetric, so only the lower triangle is shown.
+            for column in 0..=row {
+                let mut collides = table.collides(row, column);
+                if ui
+                    .checkbox(&mut collides, "")
+                    .on_hover_text(format!("{} and {}", table.name(row), table.name(column)))
+                    .changed()
+                {
+                    table.set_collides(row, column, collides);
+                }
+            }
+            ui.end_row();
+        }
+    });
+
+    let changed = table.filters != sandbox.groups.filters;
+    sandbox.groups = table;
+    if changed {
+        sandbox.apply_groups(ctx)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn set_collides_keeps_the_matrix_symmetric() {
+        let mut table = GroupTable::default();
+        table.set_collides(1, 3, false);
+        assert!(!table.collides(1, 3));
+        assert!(!table.collides(3, 1));
+        assert!(table.collides(1, 2));
+
+        table.set_collides(3, 1, true);
+        assert!(table.collides(1, 3));
+        assert!(table.collides(3, 1));
+    }
+
+    #[test]
+    fn interaction_groups_follow_the_filter_row() {
+        let mut table = GroupTable::default();
+        table.set_collides(2, 0, false);
+        let groups = table.interaction_groups(2);
+        assert_eq!(groups.memberships.bits(), 1 << 2);
+        assert_eq!(groups.filter.bits(), u16::MAX as u32 & !1);
+    }
+
+    #[test]
+    fn normalize_fills_in_a_partial_table() {
+        let mut table = GroupTable {
+            count: 40,
+            names: vec!["Floor".to_string()],
+            filters: vec![0b10],
+        };
+        table.normalize();
+        assert_eq!(table.count, GROUP_COUNT);
+        assert_eq!(table.names.len(), GROUP_COUNT);
+        assert_eq!(table.name(0), "Floor");
+        assert_eq!(table.name(5), "Group 5");
+        assert_eq!(table.filters.len(), GROUP_COUNT);
+        assert!(table.collides(0, 1) && !table.collides(0, 0));
+        assert!(table.collides(7, 9));
+
+        table.count = 0;
+        table.normalize();
+        assert_eq!(table.count, 1);
+    }
+}
@@ -0,0 +1,70 @@
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    sync::mpsc::{self, Receiver},
+    time::{Duration, Instant},
+};
+
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+
+/// How long a file has to stay untouched before it counts as changed.
+///
+/// Editors and image tools often write a file in several steps.
+const SETTLE_TIME: Duration = Duration::from_millis(200);
+
+/// Watches the assets directory for changed files.
+pub struct AssetWatcher {
+    _watcher: RecommendedWatcher,
+    events: Receiver<notify::Result<Event>>,
+    root: PathBuf,
+    /// Changed paths and when they were last touched.
+    pending: HashMap<PathBuf, Instant>,
+}
+
+impl AssetWatcher {
+    pub fn new(root: &Path) -> notify::Result<Self> {
+        let (sender, events) = mpsc::channel();
+        let mut watcher = notify::recommended_watcher(sender)?;
+        watcher.watch(root, RecursiveMode::Recursive)?;
+        Ok(Self {
+            _watcher: watcher,
+            events,
+            // Events carry absolute paths.
+            root: root.canonicalize().unwrap_or_else(|_| root.to_path_buf()),
+            pending: HashMap::new(),
+        })
+    }
+
+    /// Paths relative to the assets directory that changed and have settled since the last call.
+    pub fn changed(&mut self) -> Vec<PathBuf> {
+        for event in self.events.try_iter() {
+            let event = match event {
+                Ok(event) => event,
+                Err(e) => {
+                    log::warn!("Asset watcher error: {e}");
+                    continue;
+                }
+            };
+            if !matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_)) {
+                continue;
+            }
+            for path in event.paths {
+                let path = path
+                    .strip_prefix(&self.root)
+                    .map(Path::to_path_buf)
+                    .unwrap_or(path);
+                self.pending.insert(path, Instant::now());
+            }
+        }
+        let settled: Vec<PathBuf> = self
+            .pending
+            .iter()
+            .filter(|(_, touched)| touched.elapsed() >= SETTLE_TIME)
+            .map(|(path, _)| path.clone())
+            .collect();
+        for path in &settled {
+            self.pending.remove(path);
+        }
+        settled
+    }
+}
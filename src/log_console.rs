@@ -0,0 +1,264 @@
+use std::{
+    collections::VecDeque,
+    fs::{File, OpenOptions},
+    io::Write,
+    path::Path,
+    sync::{LazyLock, Mutex, PoisonError},
+    time::Instant,
+};
+
+use egui::{Color32, RichText};
+use log::{Level, LevelFilter, Log, Metadata, Record};
+
+/// How many records the logger and the console keep.
+const CAPACITY: usize = 2000;
+const LOG_FILE: &str = "sandbox.log";
+
+static LOGGER: LazyLock<Logger> = LazyLock::new(|| Logger {
+    started: Instant::now(),
+    state: Mutex::new(State {
+        records: VecDeque::with_capacity(CAPACITY),
+        next: 0,
+        file: None,
+    }),
+});
+
+#[derive(Clone)]
+struct Entry {
+    seq: u64,
+    /// Seconds since the logger was installed.
+    time: f32,
+    level: Level,
+    target: String,
+    message: String,
+}
+
+struct State {
+    records: VecDeque<Entry>,
+    next: u64,
+    file: Option<File>,
+}
+
+/// Captures log records from the engine and the sandbox into a ring buffer.
+struct Logger {
+    started: Instant,
+    state: Mutex<State>,
+}
+
+impl Logger {
+    fn state(&self) -> std::sync::MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl Log for Logger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= log::max_level()
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+        let mut state = self.state();
+        let entry = Entry {
+            seq: state.next,
+            time: self.started.elapsed().as_secs_f32(),
+            level: record.level(),
+            target: record.target().to_string(),
+            message: record.args().to_string(),
+        };
+        if let Some(file) = &mut state.file {
+            // A failing log file must not log about itself.
+            let _ = writeln!(file, "{}", entry.line());
+        }
+        state.next += 1;
+        if state.records.len() == CAPACITY {
+            state.records.pop_front();
+        }
+        state.records.push_back(entry);
+    }
+
+    fn flush(&self) {
+        if let Some(file) = &mut self.state().file {
+            let _ = file.flush();
+        }
+    }
+}
+
+/// Installs the logger. Call before starting the engine so its records are captured too.
+pub fn install() {
+    match log::set_logger(&*LOGGER) {
+        Ok(()) => log::set_max_level(LevelFilter::Info),
+        Err(e) => eprintln!("Failed to install the logger: {e}"),
+    }
+}
+
+/// Mirrors new records to the given file, or stops mirroring with `None`.
+fn set_log_file(path: Option<&Path>) -> std::io::Result<()> {
+    let file = match path {
+        Some(path) => Some(OpenOptions::new().create(true).append(true).open(path)?),
+        None => None,
+    };
+    LOGGER.state().file = file;
+    Ok(())
+}
+
+fn entries_since(seq: u64) -> Vec<Entry> {
+    let state = LOGGER.state();
+    let skip = state.records.partition_point(|entry| entry.seq < seq);
+    state.records.range(skip..).cloned().collect()
+}
+
+impl Entry {
+    fn line(&self) -> String {
+        format!(
+            "[{:>9.3} {:<5} {}] {}",
+            self.time, self.level, self.target, self.message
+        )
+    }
+}
+
+/// The log console window, or a panel at the bottom while docked.
+pub struct LogConsole {
+    entries: VecDeque<Entry>,
+    next: u64,
+    /// Most verbose level shown.
+    filter: LevelFilter,
+    search: String,
+    paused: bool,
+    docked: bool,
+    log_file: bool,
+    status: String,
+}
+
+impl LogConsole {
+    pub fn new() -> Self {
+        Self {
+            entries: VecDeque::with_capacity(CAPACITY),
+            next: 0,
+            filter: LevelFilter::Info,
+            search: String::new(),
+            paused: false,
+            docked: false,
+            log_file: false,
+            status: String::new(),
+        }
+    }
+
+    /// Picks up new records unless paused.
+    fn fetch(&mut self) {
+        if self.paused {
+            return;
+        }
+        for entry in entries_since(self.next) {
+            self.next = entry.seq + 1;
+            if self.entries.len() == CAPACITY {
+                self.entries.pop_front();
+            }
+            self.entries.push_back(entry);
+        }
+    }
+
+    fn visible(&self) -> impl Iterator<Item = &Entry> {
+        let search = self.search.to_lowercase();
+        self.entries.iter().filter(move |entry| {
+            entry.level <= self.filter
+                && (search.is_empty()
+                    || entry.message.to_lowercase().contains(&search)
+                    || entry.target.to_lowercase().contains(&search))
+        })
+    }
+
+    /// Shows the console, docked or as a window, while `open` is set.
+    pub fn show(&mut self, ectx: &egui::Context, open: &mut bool) {
+        if !*open {
+            return;
+        }
+        self.fetch();
+        if self.docked {
+            egui::TopBottomPanel::bottom("log_console")
+                .resizable(true)
+                .default_height(200.0)
+                .show(ectx, |ui| self.ui(ui));
+        } else {
+            egui::Window::new("Log")
+                .open(open)
+                .default_size([600.0, 300.0])
+                .show(ectx, |ui| self.ui(ui));
+        }
+    }
+
+    fn ui(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            egui::ComboBox::from_id_salt("log_level")
+                .selected_text(self.filter.as_str())
+                .show_ui(ui, |ui| {
+                    for filter in LevelFilter::iter().skip(1) {
+                        ui.selectable_value(&mut self.filter, filter, filter.as_str());
+                    }
+                });
+            // Records more verbose than this are not captured at all.
+            let current = log::max_level();
+            let mut capture = current;
+            egui::ComboBox::from_id_salt("log_capture")
+                .selected_text(format!("Capture {}", capture.as_str()))
+                .show_ui(ui, |ui| {
+                    for filter in LevelFilter::iter().skip(1) {
+                        ui.selectable_value(&mut capture, filter, filter.as_str());
+                    }
+                });
+            if capture != current {
+                log::set_max_level(capture);
+            }
+            ui.add(egui::TextEdit::singleline(&mut self.search).hint_text("Search"));
+            ui.toggle_value(&mut self.paused, "Pause");
+            if ui.button("Copy all").clicked() {
+                let text: Vec<String> = self.visible().map(Entry::line).collect();
+                ui.ctx().copy_text(text.join("\n"));
+            }
+            if ui.button("Clear").clicked() {
+                self.entries.clear();
+            }
+            ui.checkbox(&mut self.docked, "Docked");
+            if ui
+                .checkbox(&mut self.log_file, format!("Write to {LOG_FILE}"))
+                .changed()
+            {
+                let path = self.log_file.then_some(Path::new(LOG_FILE));
+                self.status = match set_log_file(path) {
+                    Ok(()) => String::new(),
+                    Err(e) => {
+                        self.log_file = false;
+                        format!("Failed to open {LOG_FILE}: {e}")
+                    }
+                };
+            }
+        });
+        if !self.status.is_empty() {
+            ui.label(&self.status);
+        }
+        ui.separator();
+
+        let entries: Vec<&Entry> = self.visible().collect();
+        let row_height = ui.text_style_height(&egui::TextStyle::Monospace);
+        egui::ScrollArea::both()
+            .auto_shrink(false)
+            .stick_to_bottom(true)
+            .show_rows(ui, row_height, entries.len(), |ui, rows| {
+                for entry in &entries[rows] {
+                    let color = match entry.level {
+                        Level::Error => Color32::LIGHT_RED,
+                        Level::Warn => Color32::YELLOW,
+                        Level::Info => ui.visuals().text_color(),
+                        Level::Debug => Color32::LIGHT_BLUE,
+                        Level::Trace => Color32::GRAY,
+                    };
+                    ui.add(
+                        egui::Label::new(RichText::new(entry.line()).monospace().color(color))
+                            .extend(),
+                    );
+                }
+            });
+    }
+}
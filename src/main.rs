@@ -1,11 +1,69 @@
-use std::{collections::HashSet, time::Duration};
+mod asset_inspector;
+mod audio;
+mod benchmark;
+mod bindings;
+mod collisions;
+mod commands;
+mod conveyors;
+mod crash;
+mod error;
+mod fields;
+mod gamepad;
+mod groups;
+#[cfg(debug_assertions)]
+mod hot_reload;
+mod log_console;
+mod materials;
+mod motors;
+mod notifications;
+mod panels;
+mod paths;
+mod physics;
+mod profiler;
+mod sandbox;
+mod scripting;
+mod soft_body;
+mod tools;
 
+use std::{
+    collections::HashSet,
+    panic::{self, AssertUnwindSafe},
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
+
+use asset_inspector::AssetInspector;
+use audio::Audio;
+use benchmark::{Benchmark, BenchmarkAction};
+use bindings::{Action, Bindings};
+use collisions::CollisionLog;
+use commands::{Command, CommandConsole};
+use conveyors::ConveyorStyle;
+use crash::{Crash, CrashAction, InputLog};
+use error::{ObjectLookup, SandboxError};
+use gamepad::GamepadInput;
+#[cfg(debug_assertions)]
+use hot_reload::AssetWatcher;
 use let_engine::prelude::{
-    gpu::{VulkanTypes, model::ModelId},
+    gpu::{
+        VulkanTypes, buffer::BufferId, material::MaterialId, model::ModelId, texture::TextureId,
+    },
     *,
 };
-use let_engine_widgets::labels::{Label, LabelCreateInfo, Labelifier};
+use let_engine_widgets::labels::{Font, Label, LabelCreateInfo, Labelifier};
+use log_console::LogConsole;
+use motors::MotorEditor;
+use notifications::{Level, Notifications};
+use panels::{Panel, Panels};
+use physics::PhysicsEditor;
+use profiler::Profiler;
+use sandbox::{BoxTexture, BreakLimits, Sandbox, Snapshot, Style, Textured};
+use scripting::{ScriptFrame, Scripting};
+use soft_body::SoftStyle;
+use tools::{Gizmos, Pan, Tools};
 const TICK_SPEED: f32 = 1.0 / 180.0;
+const TEXTURE: &str = "textures/twister_tex.png";
+const FONT: &str = "fonts/Px437_CL_Stingray_8x16.ttf";
 
 type Ctx<'a> = EngineContext<'a>;
 
@@ -23,6 +81,9 @@ fn main() {
         .build()
         .unwrap();
 
+    log_console::install();
+    crash::install_hook();
+
     let_engine::start(
         EngineSettings::default()
             .window(window_builder)
@@ -34,33 +95,53 @@ fn main() {
 
 struct Game {
     txt: String,
-    last: bool,
-    last2: bool,
-    right: bool,
-    mouse_lock: Vec2,
-    camera_lock: Vec2,
+    camera_pan: Pan,
     egui_focused: bool,
-    fixed: bool,
     color: Color,
-    object_transform: Transform,
-    rotation: f32,
-    select: bool,
-    selected_object: Option<ObjectId>,
-    targeted_object: Option<ObjectId>,
-    spawned_objects: HashSet<ObjectId>,
-    place_indicator: ObjectId,
-    square: Appearance<VulkanTypes>,
+    sandbox: Sandbox,
+    tools: Tools,
     labelifier: Labelifier<VulkanTypes>,
-    rtext: Label<VulkanTypes>,
-    gtext: Label<VulkanTypes>,
-    btext: Label<VulkanTypes>,
-    arrow: ObjectId,
-    arrow_model: ModelId<Vec2>,
+    /// The red, green and blue text labels.
+    labels: [Label<VulkanTypes>; 3],
+    label_objects: Vec<ObjectId>,
+    /// Fonts to choose the label font from, with their names.
+    fonts: Vec<(String, Font)>,
+    /// Index of the label font in `fonts`.
+    font: usize,
+    square_resources: SquareResources,
+    #[cfg(debug_assertions)]
+    asset_watcher: Option<AssetWatcher>,
     fps_cap: f64,
+    benchmark: Benchmark,
+    profiler: Profiler,
+    bindings: Bindings,
+    /// Time scale to restore when unpausing.
+    paused_scale: Option<f32>,
+    gamepad: GamepadInput,
+    gamepad_cursor: ObjectId,
+    notifications: Notifications,
+    input_log: InputLog,
+    log_console: LogConsole,
+    console: CommandConsole,
+    /// Whether egui has keyboard focus, e.g. for the console input.
+    egui_typing: bool,
+    scripting: Scripting,
+    asset_inspector: AssetInspector,
+    audio: Audio,
+    physics: PhysicsEditor,
+    collisions: CollisionLog,
+    motor_editor: MotorEditor,
+    panels: Panels,
+    /// A caught panic, shown in the crash dialog until dismissed.
+    crash: Option<Crash>,
 }
 
 impl Game {
     pub fn new(ctx: Ctx) -> Result<Self, ()> {
+        Self::init(&ctx).map_err(|e| log::error!("Failed to start the sandbox: {e}"))
+    }
+
+    fn init(ctx: &Ctx) -> error::Result<Self> {
         ctx.scene.root_view_mut().set_scaling(CameraScaling::Expand);
         ctx.scene.root_view_mut().camera_mut().size = Vec2::splat(0.001);
         ctx.gpu
@@ -73,10 +154,10 @@ impl Game {
                     .topology(Topology::LineStrip)
                     .line_width(2.0)
                     .build()
-                    .unwrap(),
+                    .map_err(SandboxError::gpu)?,
                 GraphicsShaders::new_default(),
             ))
-            .unwrap();
+            .map_err(SandboxError::gpu)?;
 
         let indicator_model = ctx
             .gpu
@@ -89,7 +170,7 @@ impl Game {
                 ],
                 vec![0, 1, 2, 3, 0]
             ))
-            .unwrap();
+            .map_err(SandboxError::gpu)?;
         let arrow_model = ctx
             .gpu
             .load_model(&model!(
@@ -101,7 +182,7 @@ impl Game {
                 ],
                 vec![0, 1, 2, 3, 1]
             ))
-            .unwrap();
+            .map_err(SandboxError::gpu)?;
 
         let color = Color::from_rgba(0.7, 0.3, 0.3, 1.0); // default color
         let color_buffer = ctx
@@ -111,7 +192,7 @@ impl Game {
                 BufferAccess::Fixed,
                 color,
             ))
-            .unwrap();
+            .map_err(SandboxError::gpu)?;
 
         let place_indicator = ObjectBuilder::new(
             AppearanceBuilder::default()
@@ -122,13 +203,13 @@ impl Game {
                     (Location::new(1, 0), Descriptor::buffer(color_buffer)),
                 ])
                 .build(&ctx.gpu)
-                .unwrap(),
+                .map_err(SandboxError::gpu)?,
         );
 
         let place_indicator = ctx
             .scene
             .add_object(ctx.scene.root_layer_id(), place_indicator)
-            .unwrap();
+            .map_err(SandboxError::scene)?;
 
         let arrow = ObjectBuilder::new(
             AppearanceBuilder::default()
@@ -140,133 +221,127 @@ impl Game {
                     (Location::new(1, 0), Descriptor::buffer(color_buffer)),
                 ])
                 .build(&ctx.gpu)
-                .unwrap(),
+                .map_err(SandboxError::gpu)?,
         );
         let arrow = ctx
             .scene
             .add_object(ctx.scene.root_layer_id(), arrow)
-            .unwrap();
+            .map_err(SandboxError::scene)?;
 
-        let last = false;
-        let last2 = false;
-        let right = false;
-        let mouse_lock = Vec2::ZERO;
-        let camera_lock = Vec2::ZERO;
-        let egui_focused = false;
-        ctx.scene
-            .root_layer_mut()
-            .set_physics_parameters(IntegrationParameters {
-                dt: TICK_SPEED,
-                normalized_allowed_linear_error: 0.0001,
-                normalized_prediction_distance: 0.001,
-                ..Default::default()
-            });
+        let ring_model = ctx
+            .gpu
+            .load_model(&Model::new(
+                (0..=32)
+                    .map(|i| Vec2::from_angle(i as f32 / 32.0 * std::f32::consts::TAU))
+                    .collect(),
+                BufferAccess::Fixed,
+            ))
+            .map_err(SandboxError::gpu)?;
+        let ring = ObjectBuilder::new(
+            AppearanceBuilder::default()
+                .visible(false)
+                .material(place_indicator_material)
+                .model(ring_model)
+                .descriptors(&[
+                    (Location::new(0, 0), Descriptor::Mvp),
+                    (Location::new(1, 0), Descriptor::buffer(color_buffer)),
+                ])
+                .build(&ctx.gpu)
+                .map_err(SandboxError::gpu)?,
+        );
+        let ring = ctx
+            .scene
+            .add_object(ctx.scene.root_layer_id(), ring)
+            .map_err(SandboxError::scene)?;
+
+        let path_model = ctx
+            .gpu
+            .load_model(&model!(
+                vec![Vec2::ZERO; Gizmos::PATH_VERTICES],
+                (0..Gizmos::PATH_VERTICES as u32).collect()
+            ))
+            .map_err(SandboxError::gpu)?;
+        let path = ObjectBuilder::new(
+            AppearanceBuilder::default()
+                .visible(false)
+                .material(place_indicator_material)
+                .model(path_model)
+                .descriptors(&[
+                    (Location::new(0, 0), Descriptor::Mvp),
+                    (Location::new(1, 0), Descriptor::buffer(color_buffer)),
+                ])
+                .build(&ctx.gpu)
+                .map_err(SandboxError::gpu)?,
+        );
+        let path = ctx
+            .scene
+            .add_object(ctx.scene.root_layer_id(), path)
+            .map_err(SandboxError::scene)?;
 
-        let fixed = false;
+        let gamepad_cursor = ObjectBuilder::new(
+            AppearanceBuilder::default()
+                .visible(false)
+                .material(place_indicator_material)
+                .model(indicator_model)
+                .descriptors(&[
+                    (Location::new(0, 0), Descriptor::Mvp),
+                    (Location::new(1, 0), Descriptor::buffer(color_buffer)),
+                ])
+                .build(&ctx.gpu)
+                .map_err(SandboxError::gpu)?,
+        );
+        let gamepad_cursor = ctx
+            .scene
+            .add_object(ctx.scene.root_layer_id(), gamepad_cursor)
+            .map_err(SandboxError::scene)?;
 
-        let object_transform: Transform = (vec2(0.0, 0.0), vec2(0.07, 0.07), 0.0).into();
-        let rotation: f32 = 0.0;
+        let egui_focused = false;
+        let physics = PhysicsEditor::new();
+        physics.settings.apply(ctx)?;
 
-        let select = false;
-        let selected_object = None;
-        let targeted_object = None;
-        let mut spawned_objects = HashSet::new();
         let txt = String::from(
             "Left mouse button: spawn object\rRight mouse button: remove object\rMiddle mouse: Zoom and pan\rEdit this text with the keyboard.",
         );
-        let mut labelifier = Labelifier::new(&ctx.gpu).unwrap();
+        let mut labelifier = Labelifier::new(&ctx.gpu).map_err(SandboxError::label)?;
 
         let font = labelifier
             .font_from_vec(
-                let_engine::asset_system::asset("fonts/Px437_CL_Stingray_8x16.ttf")
-                    .unwrap()
+                let_engine::asset_system::asset(FONT)
+                    .map_err(SandboxError::asset(FONT))?
                     .to_vec(),
             )
-            .unwrap();
-        let fsize = 35.0;
-        let rtext = Label::new(
-            LabelCreateInfo {
-                transform: Transform::with_size(Vec2::splat(0.001)),
-                text_color: Color::from_rgba(1.0, 0.0, 0.0, 1.0),
-                text: txt.clone(),
-                scale: Vec2::splat(fsize),
-                extent: UVec2::splat(2000),
-                font,
-                align: Direction::Nw,
-            },
-            &mut labelifier,
-            &ctx.gpu,
-        )
-        .unwrap();
-        let gtext = Label::new(
-            LabelCreateInfo {
-                transform: Transform::with_size(Vec2::splat(0.001)),
-                text_color: Color::from_rgba(0.0, 1.0, 0.0, 1.0),
-                text: txt.clone(),
-                scale: Vec2::splat(fsize),
-                extent: UVec2::splat(2000),
-                font,
-                align: Direction::Center,
-            },
-            &mut labelifier,
-            &ctx.gpu,
-        )
-        .unwrap();
-        let btext = Label::new(
-            LabelCreateInfo {
-                transform: Transform::with_size(Vec2::splat(0.001)),
-                text_color: Color::from_rgba(0.0, 0.0, 1.0, 1.0),
-                text: txt.clone(),
-                scale: Vec2::splat(fsize),
-                extent: UVec2::splat(2000),
-                font,
-                align: Direction::So,
-            },
-            &mut labelifier,
-            &ctx.gpu,
-        )
-        .unwrap();
+            .map_err(SandboxError::label)?;
+        let labels = create_labels(ctx, &mut labelifier, font, &txt)?;
+        let mut label_objects = Vec::with_capacity(labels.len());
+        for label in &labels {
+            label_objects.push(
+                ctx.scene
+                    .add_object(
+                        ctx.scene.root_layer_id(),
+                        ObjectBuilder::new(
+                            label
+                                .appearance()
+                                .build(&ctx.gpu)
+                                .map_err(SandboxError::gpu)?,
+                        ),
+                    )
+                    .map_err(SandboxError::scene)?,
+            );
+        }
 
-        ctx.scene
-            .add_object(
-                ctx.scene.root_layer_id(),
-                ObjectBuilder::new(rtext.appearance().build(&ctx.gpu).unwrap()),
-            )
-            .unwrap();
-        ctx.scene
-            .add_object(
-                ctx.scene.root_layer_id(),
-                ObjectBuilder::new(gtext.appearance().build(&ctx.gpu).unwrap()),
-            )
-            .unwrap();
-        ctx.scene
-            .add_object(
-                ctx.scene.root_layer_id(),
-                ObjectBuilder::new(btext.appearance().build(&ctx.gpu).unwrap()),
-            )
-            .unwrap();
-
-        let rusty = ctx
-            .gpu
-            .load_texture(
-                &Texture::from_bytes(
-                    let_engine::asset_system::asset("textures/twister_tex.png")
-                        .unwrap()
-                        .to_vec(),
-                    ImageFormat::Png,
-                    TextureSettingsBuilder::default()
-                        .format(Format::Rgba8Unorm)
-                        .build()
-                        .unwrap(),
-                )
-                .unwrap(),
-            )
-            .unwrap();
+        let rusty = load_texture(
+            ctx,
+            TEXTURE,
+            let_engine::asset_system::asset(TEXTURE)
+                .map_err(SandboxError::asset(TEXTURE))?
+                .to_vec(),
+            ImageFormat::Png,
+        )?;
 
         let square_material = ctx
             .gpu
             .load_material::<TVert>(&Material::default_textured())
-            .unwrap();
+            .map_err(SandboxError::gpu)?;
 
         let square = Model::new(
             vec![
@@ -280,16 +355,64 @@ impl Game {
             BufferAccess::Fixed,
         );
 
-        let square = AppearanceBuilder::default()
-            .model(ctx.gpu.load_model::<TVert>(&square).unwrap())
-            .material(square_material)
-            .descriptors(&[
-                (Location::new(0, 0), Descriptor::Mvp),
-                (Location::new(1, 0), Descriptor::buffer(color_buffer)),
-                (Location::new(2, 0), Descriptor::Texture(rusty)),
-            ])
-            .build(&ctx.gpu)
-            .unwrap();
+        let mut color_buffers = vec![color_buffer];
+        for group in 1..groups::GROUP_COUNT {
+            let [r, g, b, a] = groups::color(group);
+            color_buffers.push(
+                ctx.gpu
+                    .load_buffer(&Buffer::from_data(
+                        BufferUsage::Uniform,
+                        BufferAccess::Fixed,
+                        Color::from_rgba(r, g, b, a),
+                    ))
+                    .map_err(SandboxError::gpu)?,
+            );
+        }
+        // A disc of radius one, textured like the boxes.
+        let mut disc = vec![tvert(0.0, 0.0, 0.0, 0.0)];
+        disc.extend((0..32).map(|i| {
+            let point = Vec2::from_angle(i as f32 / 32.0 * std::f32::consts::TAU);
+            tvert(point.x, point.y, point.x, point.y)
+        }));
+        let disc_indices = (0..32u32)
+            .flat_map(|i| [0, i + 1, (i + 1) % 32 + 1])
+            .collect();
+
+        let mut sensor_colors = [color_buffer; 2];
+        for (buffer, color) in sensor_colors.iter_mut().zip([
+            Color::from_rgba(0.3, 0.8, 0.4, 0.35),
+            Color::from_rgba(0.4, 1.0, 0.5, 0.7),
+        ]) {
+            *buffer = ctx
+                .gpu
+                .load_buffer(&Buffer::from_data(
+                    BufferUsage::Uniform,
+                    BufferAccess::Fixed,
+                    color,
+                ))
+                .map_err(SandboxError::gpu)?;
+        }
+        let square_resources = SquareResources {
+            material: square_material,
+            model: ctx
+                .gpu
+                .load_model::<TVert>(&square)
+                .map_err(SandboxError::gpu)?,
+            disc: ctx
+                .gpu
+                .load_model(&model!(disc, disc_indices))
+                .map_err(SandboxError::gpu)?,
+            color_buffers,
+            sensor_colors,
+        };
+        let Textured {
+            squares,
+            sensor,
+            sensor_occupied,
+            circle,
+            ..
+        } = square_resources.textured(ctx, rusty)?;
+        let square = squares[0].clone();
         // ::new_instanced(Some(Model::Square), Some(rusty));
 
         let mut platform = ObjectBuilder::new(square.clone());
@@ -304,287 +427,662 @@ impl Game {
         let platform = ctx
             .scene
             .add_object(ctx.scene.root_layer_id(), platform)
-            .unwrap();
-        spawned_objects.insert(platform);
+            .map_err(SandboxError::scene)?;
+        let snap_color = ctx
+            .gpu
+            .load_buffer(&Buffer::from_data(
+                BufferUsage::Uniform,
+                BufferAccess::Fixed,
+                Color::from_rgba(1.0, 0.9, 0.3, 1.0),
+            ))
+            .map_err(SandboxError::gpu)?;
+        let style = Style {
+            sensor,
+            sensor_occupied,
+            fields: field_style(ctx, place_indicator_material)?,
+            circle,
+            soft: SoftStyle {
+                material: square_resources.material,
+                texture: rusty,
+                color: color_buffer,
+            },
+            conveyor: ConveyorStyle {
+                material: square_resources.material,
+                texture: rusty,
+                color: color_buffer,
+            },
+            snap: AppearanceBuilder::default()
+                .material(place_indicator_material)
+                .model(ring_model)
+                .descriptors(&[
+                    (Location::new(0, 0), Descriptor::Mvp),
+                    (Location::new(1, 0), Descriptor::buffer(snap_color)),
+                ])
+                .build(&ctx.gpu)
+                .map_err(SandboxError::gpu)?,
+        };
+        let sandbox = Sandbox::new(squares, style, platform);
+        sandbox.apply_groups(ctx)?;
+
+        let tools = Tools::new(Gizmos {
+            rect: place_indicator,
+            arrow,
+            arrow_model,
+            ring,
+            path,
+            path_model,
+        });
+
+        let gamepad = GamepadInput::new();
+        let mut notifications = Notifications::new();
+        if !gamepad.available() {
+            notifications.push(Level::Warning, "Gamepad support unavailable");
+        }
+        #[cfg(debug_assertions)]
+        let asset_watcher = match AssetWatcher::new(&assets_dir()) {
+            Ok(watcher) => Some(watcher),
+            Err(e) => {
+                notifications.push(
+                    Level::Warning,
+                    format!("Asset hot reloading unavailable: {e}"),
+                );
+                None
+            }
+        };
 
         Ok(Self {
             txt,
-            last,
-            last2,
-            right,
-            mouse_lock,
-            camera_lock,
+            camera_pan: Pan::new(),
             egui_focused,
-            fixed,
             color,
-            object_transform,
-            rotation,
-            select,
-            selected_object,
-            targeted_object,
-            spawned_objects,
-            place_indicator,
-            square,
-            rtext,
-            gtext,
-            btext,
-            arrow,
-            arrow_model,
+            sandbox,
+            tools,
+            labels,
+            label_objects,
+            fonts: vec![(FONT.to_string(), font)],
+            font: 0,
+            square_resources,
+            #[cfg(debug_assertions)]
+            asset_watcher,
             fps_cap: 180.0,
             labelifier,
+            benchmark: Benchmark::new(),
+            profiler: Profiler::new(),
+            bindings: Bindings::load(),
+            paused_scale: None,
+            gamepad,
+            gamepad_cursor,
+            notifications,
+            input_log: InputLog::new(),
+            log_console: LogConsole::new(),
+            console: CommandConsole::new(),
+            egui_typing: false,
+            scripting: Scripting::new(),
+            asset_inspector: AssetInspector::new(&[TEXTURE, FONT]),
+            audio: Audio::new(),
+            physics,
+            collisions: CollisionLog::new(),
+            motor_editor: MotorEditor::new(),
+            panels: Panels::new(),
+            crash: None,
         })
     }
-}
 
-impl let_engine::Game for Game {
-    fn update(&mut self, ctx: Ctx) -> Result<(), ()> {
-        self.labelifier.update(&ctx.gpu).unwrap();
-        if self.egui_focused {
-            return Ok(());
+    /// Camera panning and zooming, available with every tool.
+    fn handle_camera(&mut self, ctx: &Ctx) {
+        self.camera_pan.update(ctx, self.bindings.down(Action::Pan));
+
+        let zoom = match (
+            self.bindings.down(Action::ZoomIn),
+            self.bindings.down(Action::ZoomOut),
+        ) {
+            (true, false) => -0.01,
+            (false, true) => 0.01,
+            _ => 0.0,
+        };
+        let camera = ctx.scene.root_view_mut().camera_mut();
+        camera.size = camera.size + camera.size * zoom;
+    }
+
+    /// The world position of the gamepad's virtual cursor while in use, or the mouse.
+    fn cursor_to_world(&self, ctx: &Ctx) -> Vec2 {
+        if self.gamepad.using() {
+            self.gamepad.cursor()
+        } else {
+            ctx.input.cursor_to_world(ctx.scene.root_view())
         }
+    }
 
-        let cursor_to_world = ctx.input.cursor_to_world(ctx.scene.root_view());
+    /// Runs an action that triggers once per key or button press.
+    fn trigger(&mut self, ctx: &Ctx, action: Action) {
+        match action {
+            Action::Exit => ctx.exit(),
+            Action::Fullscreen => {
+                let Some(window) = ctx.window() else {
+                    self.notifications.error(SandboxError::NoWindow);
+                    return;
+                };
+                window.set_fullscreen(if window.fullscreen().is_some() {
+                    None
+                } else {
+                    Some(Fullscreen::Borderless(None))
+                });
+            }
+            Action::Pause => self.toggle_pause(ctx),
+            Action::Console => self.console.toggle(),
+            action => self.tools.hotkey(ctx, &mut self.sandbox, action),
+        }
+    }
 
-        if !self.select {
-            self.object_transform.position = cursor_to_world;
-            ctx.scene
-                .object_mut(self.place_indicator)
-                .unwrap()
-                .transform = Transform::with_position_rotation(
-                self.object_transform.position,
-                self.object_transform.rotation,
-            );
+    fn toggle_pause(&mut self, ctx: &Ctx) {
+        if let Some(scale) = self.paused_scale.take() {
+            ctx.time.set_scale(scale);
+            self.notifications.push(Level::Info, "Resumed");
+        } else {
+            self.paused_scale = Some(ctx.time.scale());
+            ctx.time.set_scale(0.0);
+            self.notifications.push(Level::Info, "Paused");
+        }
+    }
 
-            {
-                let apperance = &mut ctx
-                    .scene
-                    .object_mut(self.place_indicator)
-                    .unwrap()
-                    .appearance;
-                apperance.set_visible(true);
-                apperance.transform_mut().size = self.object_transform.size;
+    /// Runs a callback behind an unwind boundary. A panic writes a crash report, pauses
+    /// time and opens the crash dialog.
+    fn guard(&mut self, callback: &'static str, ctx: &Ctx, f: impl FnOnce(&mut Self)) {
+        let Err(payload) = panic::catch_unwind(AssertUnwindSafe(|| f(self))) else {
+            return;
+        };
+        let panic = crash::take_panic(&*payload);
+        let camera = ctx.scene.root_view().camera();
+        let settings = crash::Settings {
+            physics: self.physics.settings.clone(),
+            time_scale: self.paused_scale.unwrap_or(ctx.time.scale()),
+            fps_cap: self.fps_cap,
+            camera_position: camera.position.to_array(),
+            camera_size: camera.size.to_array(),
+            tool: self.tools.active_name(),
+        };
+        // The scene may be what panicked, so reading it gets its own boundary.
+        let snapshot = panic::catch_unwind(AssertUnwindSafe(|| -> error::Result<_> {
+            Ok((self.sandbox.snapshot(ctx)?, self.omitted_soft_bodies(ctx)?))
+        }))
+        .ok()
+        .and_then(Result::ok);
+        self.crash = Some(Crash::new(
+            callback,
+            panic,
+            settings,
+            &self.input_log,
+            snapshot,
+        ));
+        if self.paused_scale.is_none() {
+            self.toggle_pause(ctx);
+        }
+    }
+
+    /// The soft bodies a crash report's snapshot leaves out.
+    fn omitted_soft_bodies(&self, ctx: &Ctx) -> error::Result<Vec<crash::OmittedSoftBody>> {
+        let mut bodies = Vec::with_capacity(self.sandbox.soft_bodies.len());
+        for body in &self.sandbox.soft_bodies {
+            let mut sum = Vec2::ZERO;
+            for &id in &body.particles {
+                sum += ctx.scene.object(id).or_stale(id)?.transform.position;
             }
-            {
-                if ctx.input.mouse_down(&MouseButton::Left) && !self.last {
-                    let mut object = ObjectBuilder::new(self.square.clone());
-                    object.set_collider(Some(
-                        ColliderBuilder::square(
-                            self.object_transform.size.x,
-                            self.object_transform.size.y,
-                        ) //trimesh(shape_data.clone())
-                        .restitution(0.0)
-                        .restitution_combine_rule(CoefficientCombineRule::Min)
-                        .build(),
-                    ));
-                    let rigid_body_type = if self.fixed {
-                        RigidBodyType::Fixed
-                    } else {
-                        RigidBodyType::Dynamic
-                    };
-                    object.set_rigid_body(Some(RigidBodyBuilder::new(rigid_body_type).build()));
-                    // object
-                    //     .appearance
-                    //     .set_layer(self.spawned_objects.len() as u32 % 4)
-                    //     .unwrap();
-                    object.transform = self.object_transform;
-                    object.transform.size = vec2(1.0, 1.0);
-                    let mut transform = *object.appearance.transform();
-                    transform.size = self.object_transform.size;
-                    object.appearance.set_transform(transform);
-                    let object = ctx
-                        .scene
-                        .add_object(ctx.scene.root_layer_id(), object)
-                        .unwrap();
-                    self.spawned_objects.insert(object);
-                }
-                self.last = ctx.input.mouse_down(&MouseButton::Left);
-
-                if ctx.input.mouse_down(&MouseButton::Right) && !self.last2 {
-                    let ids = ctx.scene.root_layer().intersections_with_ray(
-                        ctx.input.cursor_to_world(ctx.scene.root_view()),
-                        vec2(0.0, 0.0),
-                        0.0,
-                        true,
-                    );
-                    for id in ids {
-                        if self.spawned_objects.remove(&id) {
-                            ctx.scene.remove_object(id);
-                        }
+            bodies.push(crash::OmittedSoftBody {
+                particles: body.particles.len(),
+                center: (sum / body.particles.len().max(1) as f32).to_array(),
+            });
+        }
+        Ok(bodies)
+    }
+
+    /// Everything `update` does. Errors are shown as toasts instead of stopping the game.
+    fn tick(&mut self, ctx: &Ctx) -> error::Result<()> {
+        let start = Instant::now();
+        self.gamepad.update(ctx);
+        self.bindings.update(ctx, self.gamepad.held());
+        for action in self.gamepad.take_pressed() {
+            self.trigger(ctx, action);
+        }
+        {
+            let size = ctx.scene.root_view().camera().size * 6.0;
+            let cursor = ctx
+                .scene
+                .object_mut(self.gamepad_cursor)
+                .or_stale(self.gamepad_cursor)?;
+            cursor.appearance.set_visible(self.gamepad.using());
+            cursor.transform.position = self.gamepad.cursor();
+            cursor.appearance.transform_mut().size = size;
+        }
+        #[cfg(debug_assertions)]
+        self.reload_assets(ctx)?;
+        let label_start = Instant::now();
+        self.labelifier
+            .update(&ctx.gpu)
+            .map_err(SandboxError::label)?;
+        let label_time = label_start.elapsed();
+        if !self.egui_focused {
+            self.handle_camera(ctx);
+        }
+        let cursor = self.cursor_to_world(ctx);
+        let tools = self.tools.update(
+            ctx,
+            &mut self.sandbox,
+            cursor,
+            self.physics.settings.tick_wait,
+            (
+                self.bindings.down(Action::Primary),
+                self.bindings.down(Action::Secondary),
+            ),
+            !self.egui_focused,
+        );
+        let fields = fields::apply(ctx, &self.sandbox, self.physics.settings.tick_wait);
+        let collisions =
+            self.collisions
+                .record(ctx, &mut self.sandbox, self.physics.settings.tick_wait);
+        let joints = self
+            .sandbox
+            .break_joints(ctx, self.physics.settings.tick_wait);
+        let paths = self
+            .sandbox
+            .follow_paths(ctx, self.physics.settings.tick_wait);
+        let soft_bodies = soft_body::update(ctx, &self.sandbox);
+        let conveyors = conveyors::apply(ctx, &mut self.sandbox, self.physics.settings.tick_wait);
+        // Keys typed into egui should not drive anything.
+        let motors = if self.egui_typing {
+            self.sandbox.drive_motors(ctx, &HashSet::new())
+        } else {
+            self.sandbox.drive_motors(ctx, self.bindings.held_keys())
+        };
+        let sound = match self.tools.take_sound() {
+            Some(path) => self.play_sound(path),
+            None => Ok(()),
+        };
+        let script = self.run_script(ctx, cursor);
+        let benchmark = self
+            .benchmark
+            .update(ctx, &mut self.sandbox, start.elapsed());
+        self.profiler.record_tick(label_time, start.elapsed());
+        tools
+            .and(fields)
+            .and(collisions)
+            .and(joints)
+            .and(paths)
+            .and(conveyors)
+            .and(soft_bodies)
+            .and(motors)
+            .and(sound)
+            .and(script)
+            .and(benchmark)
+    }
+
+    /// Plays a sound from the asset packs.
+    fn play_sound(&mut self, path: &str) -> error::Result<()> {
+        let bytes = let_engine::asset_system::asset(path).map_err(SandboxError::asset(path))?;
+        self.audio.play(path, bytes.to_vec())
+    }
+
+    /// Runs the script's tick and the commands it queued.
+    fn run_script(&mut self, ctx: &Ctx, cursor: Vec2) -> error::Result<()> {
+        if !self.scripting.running() {
+            return Ok(());
+        }
+        let mut frame = ScriptFrame {
+            cursor,
+            primary: self.bindings.down(Action::Primary),
+            secondary: self.bindings.down(Action::Secondary),
+            keys: self.bindings.held_keys().clone(),
+            next_number: self.sandbox.next_number(),
+            ..Default::default()
+        };
+        for &id in &self.sandbox.spawned_objects {
+            let object = ctx.scene.object(id).or_stale(id)?;
+            let velocity = object
+                .rigid_body()
+                .map(|body| vec2(body.linvel().x, body.linvel().y))
+                .unwrap_or_default();
+            if let Some(number) = self.sandbox.number(id) {
+                frame
+                    .objects
+                    .insert(number, (object.transform.position, velocity));
+            }
+        }
+        let run = self
+            .scripting
+            .update(frame, self.physics.settings.tick_wait * ctx.time.scale())?;
+        if run.clear {
+            self.sandbox.clear(ctx);
+        }
+        // Later commands still run after a failure, so the numbers `spawn_box` handed out
+        // keep matching the objects.
+        let mut result = Ok(());
+        for command in run.commands {
+            let spawn = matches!(command, Command::Spawn { .. });
+            match self.run_command(ctx, command) {
+                Ok(output) => log::debug!("[script] {output}"),
+                Err(e) => {
+                    if spawn {
+                        self.sandbox.skip_number();
+                    }
+                    if result.is_ok() {
+                        result = Err(e);
                     }
                 }
-                self.last2 = ctx.input.mouse_down(&MouseButton::Right);
             }
-        } else {
-            if ctx.input.mouse_down(&MouseButton::Left)
-                && !self.last
-                && let Some(id) = ctx.scene.root_layer().cast_ray(
-                    ctx.input.cursor_to_world(ctx.scene.root_view()),
-                    vec2(0.0, 0.0),
-                    0.0,
-                    true,
-                )
-            {
-                self.selected_object = self.spawned_objects.get(&id).cloned();
+        }
+        result
+    }
+
+    /// Runs a console command and returns what to print.
+    fn run_command(&mut self, ctx: &Ctx, command: Command) -> error::Result<String> {
+        Ok(match command {
+            Command::Help => Command::help(),
+            Command::Spawn {
+                position,
+                size,
+                body,
+            } => {
+                let id = self
+                    .sandbox
+                    .spawn_square(ctx, (position, size, 0.0).into(), body)?;
+                format!("spawned #{}", self.sandbox.number(id).unwrap_or_default())
             }
-            if ctx.input.mouse_down(&MouseButton::Left) {
-                ctx.scene
-                    .object_mut(self.arrow)
-                    .unwrap()
-                    .appearance
-                    .set_visible(true);
-                if let Some(id) = self.selected_object {
-                    let object = ctx.scene.object(id).unwrap();
-                    ctx.scene.object_mut(self.arrow).unwrap().transform.position =
-                        object.transform.position;
-                    let arrow = ctx.scene.object(self.arrow).unwrap();
-                    let (length, angle) = if let Some(second_object) =
-                        ctx.scene.root_layer().cast_ray(
-                            ctx.input.cursor_to_world(ctx.scene.root_view()),
-                            vec2(0.0, 0.0),
-                            0.0,
-                            true,
-                        ) {
-                        let object2 = ctx.scene.object(second_object).unwrap();
-                        let position = object2.transform.position;
-                        self.targeted_object = Some(second_object);
-                        (
-                            arrow.transform.position.distance(position),
-                            angle_between(arrow.transform.position, position),
-                        )
-                    } else {
-                        self.targeted_object = None;
-                        (
-                            arrow.transform.position.distance(cursor_to_world),
-                            angle_between(arrow.transform.position, cursor_to_world),
-                        )
-                    };
-                    if length == 0.0 {
-                        ctx.scene
-                            .object_mut(self.arrow)
-                            .unwrap()
-                            .appearance
-                            .set_visible(false);
-                    };
-                    {
-                        ctx.gpu
-                            .model(self.arrow_model)
-                            .unwrap()
-                            .write_vertices(
-                                |vertices| {
-                                    vertices[1..3].copy_from_slice(&[
-                                        vec2(length, 0.0),
-                                        vec2(length - 0.05, 0.02),
-                                        vec2(length - 0.05, -0.02),
-                                    ]);
-                                },
-                                4,
-                            )
-                            .unwrap();
-                        ctx.scene.object_mut(self.arrow).unwrap().transform.rotation = angle;
-                    }
-                } else {
-                    ctx.scene
-                        .object_mut(self.arrow)
-                        .unwrap()
-                        .appearance
-                        .set_visible(false);
+            Command::Gravity(gravity) => {
+                self.physics.settings.gravity = gravity.to_array();
+                ctx.scene.root_layer_mut().set_gravity(gravity);
+                format!("gravity set to {gravity}")
+            }
+            Command::TimeScale(scale) => {
+                // While paused the scale applies on unpause.
+                match &mut self.paused_scale {
+                    Some(paused_scale) => *paused_scale = scale,
+                    None => ctx.time.set_scale(scale),
+                }
+                format!("time scale set to {scale}")
+            }
+            Command::Physics { parameter, value } => {
+                if let Err(e) = self.physics.settings.set(&parameter, value) {
+                    return Ok(e);
+                }
+                self.physics.settings.apply(ctx)?;
+                format!("{parameter} set to {value}")
+            }
+            Command::Clear => {
+                self.sandbox.clear(ctx);
+                "cleared".to_string()
+            }
+            Command::Select(number) => match self.sandbox.by_number(number) {
+                Some(id) => {
+                    self.sandbox.selected_object = Some(id);
+                    format!("selected #{number}")
+                }
+                None => format!("no object #{number}"),
+            },
+            Command::Joint { kind, a, b } => {
+                let (Some(id_a), Some(id_b)) =
+                    (self.sandbox.by_number(a), self.sandbox.by_number(b))
+                else {
+                    return Ok(format!("no objects #{a} and #{b}"));
                 };
-            } else {
-                ctx.scene
-                    .object_mut(self.arrow)
-                    .unwrap()
-                    .appearance
-                    .set_visible(false);
+                if id_a == id_b {
+                    return Ok("cannot join an object to itself".to_string());
+                }
+                // Anchored at the center of the second object.
+                let position_a = ctx.scene.object(id_a).or_stale(id_a)?.transform.position;
+                let position_b = ctx.scene.object(id_b).or_stale(id_b)?.transform.position;
+                self.sandbox.add_joint(
+                    ctx,
+                    (id_a, id_b),
+                    kind,
+                    (position_b - position_a, Vec2::ZERO),
+                    BreakLimits::default(),
+                )?;
+                format!("joined #{a} and #{b}")
             }
-            if !ctx.input.mouse_down(&MouseButton::Left)
-                && self.last
-                && let (Some(id), Some(target_id)) = (&self.selected_object, &self.targeted_object)
-                && id != target_id
-            {
-                let object = ctx.scene.object(*id).unwrap();
-                let target_object = ctx.scene.object(*target_id).unwrap();
-                let _handle = ctx.scene.add_joint(
-                    *id,
-                    *target_id,
-                    FixedJointBuilder::new()
-                        .local_anchor1(target_object.transform.position - object.transform.position)
-                        .local_anchor2(vec2(0.0, 0.0)),
-                    true,
-                );
-                self.targeted_object = None;
+            Command::Save(name) => {
+                let path = scene_path(&name);
+                self.sandbox.snapshot(ctx)?.write(&path)?;
+                format!("saved to {}", path.display())
             }
-            self.last = ctx.input.mouse_down(&MouseButton::Left);
-            if let Some(id) = self.selected_object {
-                let object_transform = ctx.scene.object(id).unwrap().transform;
-                let place_indicator = ctx.scene.object_mut(self.place_indicator).unwrap();
-                place_indicator.appearance.set_visible(true);
-                place_indicator.transform = object_transform;
-                place_indicator.appearance.transform_mut().size = object_transform.size;
-            } else {
-                self.selected_object = None;
-                ctx.scene
-                    .object_mut(self.place_indicator)
-                    .unwrap()
-                    .appearance
-                    .set_visible(false);
+            Command::Load(name) => {
+                let path = scene_path(&name);
+                self.load_scene(ctx, &Snapshot::read(&path)?)?;
+                format!("loaded {}", path.display())
+            }
+            Command::List => {
+                let mut objects: Vec<(u32, Vec2)> = Vec::new();
+                for &id in &self.sandbox.spawned_objects {
+                    let position = ctx.scene.object(id).or_stale(id)?.transform.position;
+                    objects.push((self.sandbox.number(id).unwrap_or_default(), position));
+                }
+                objects.sort_by_key(|(number, _)| *number);
+                objects
+                    .iter()
+                    .map(|(number, position)| {
+                        format!("#{number} at ({:.2}, {:.2})", position.x, position.y)
+                    })
+                    .collect::<Vec<_>>()
+                    .join("\n")
             }
+            Command::Remove(number) => match self.sandbox.by_number(number) {
+                Some(id) if id == self.sandbox.ground => "the ground cannot be removed".to_string(),
+                Some(id) => {
+                    self.sandbox.remove_object(ctx, id);
+                    format!("removed #{number}")
+                }
+                None => format!("no object #{number}"),
+            },
+            Command::Velocity { number, velocity } => {
+                let Some(id) = self.sandbox.by_number(number) else {
+                    return Ok(format!("no object #{number}"));
+                };
+                match ctx.scene.object_mut(id).or_stale(id)?.rigid_body_mut() {
+                    Some(body) => {
+                        body.set_linvel(velocity.into(), true);
+                        format!("#{number} moving at {velocity}")
+                    }
+                    None => format!("#{number} has no rigid body"),
+                }
+            }
+        })
+    }
+
+    /// Reloads the texture, the font and images dropped onto boxes when they change in
+    /// the assets directory.
+    #[cfg(debug_assertions)]
+    fn reload_assets(&mut self, ctx: &Ctx) -> error::Result<()> {
+        let Some(watcher) = &mut self.asset_watcher else {
+            return Ok(());
+        };
+        for path in watcher.changed() {
+            if path == Path::new(TEXTURE) {
+                self.reload_texture(ctx)?;
+            } else if path == Path::new(FONT) {
+                self.reload_font(ctx)?;
+            } else if let Some(key) = self.box_texture_at(&assets_dir().join(&path)) {
+                self.load_box_texture(ctx, &key)?;
+                self.notifications
+                    .push(Level::Info, format!("Reloaded {}", path.display()));
+            }
+        }
+        Ok(())
+    }
+
+    /// The path a box image was loaded under, if it is the file at `path`.
+    #[cfg(debug_assertions)]
+    fn box_texture_at(&self, path: &Path) -> Option<String> {
+        let path = path.canonicalize().ok()?;
+        self.sandbox
+            .box_texture_paths()
+            .find(|key| Path::new(key).canonicalize().is_ok_and(|key| key == path))
+            .map(str::to_string)
+    }
+
+    /// Swaps the sandbox texture on every spawned object, keeping their sizes.
+    #[cfg(debug_assertions)]
+    fn reload_texture(&mut self, ctx: &Ctx) -> error::Result<()> {
+        let bytes = std::fs::read(assets_dir().join(TEXTURE))?;
+        let texture = load_texture(ctx, TEXTURE, bytes, ImageFormat::Png)?;
+        let textured = self.square_resources.textured(ctx, texture)?;
+        self.sandbox.set_texture(ctx, textured)?;
+        self.notifications
+            .push(Level::Info, format!("Reloaded {TEXTURE}"));
+        Ok(())
+    }
+
+    /// Reloads the default font, and uses it for the labels if selected.
+    #[cfg(debug_assertions)]
+    fn reload_font(&mut self, ctx: &Ctx) -> error::Result<()> {
+        let bytes = std::fs::read(assets_dir().join(FONT))?;
+        let font = self
+            .labelifier
+            .font_from_vec(bytes)
+            .map_err(SandboxError::label)?;
+        self.fonts[0].1 = font;
+        if self.font == 0 {
+            self.set_font(ctx, 0)?;
+        }
+        self.notifications
+            .push(Level::Info, format!("Reloaded {FONT}"));
+        Ok(())
+    }
+
+    /// Recreates the labels with one of the loaded fonts.
+    fn set_font(&mut self, ctx: &Ctx, index: usize) -> error::Result<()> {
+        self.font = index;
+        let font = self.fonts[index].1;
+        self.labels = create_labels(ctx, &mut self.labelifier, font, &self.txt)?;
+        for (label, &id) in self.labels.iter().zip(&self.label_objects) {
+            let appearance = label
+                .appearance()
+                .build(&ctx.gpu)
+                .map_err(SandboxError::gpu)?;
+            ctx.scene.object_mut(id).or_stale(id)?.appearance = appearance;
         }
+        Ok(())
+    }
+
+    /// Loads an image for single boxes into the sandbox, replacing an older copy.
+    fn load_box_texture(&mut self, ctx: &Ctx, path: &str) -> error::Result<()> {
+        let format = image_format(Path::new(path));
+        let texture = load_texture(ctx, path, std::fs::read(path)?, format)?;
+        let squares = self.square_resources.squares(ctx, texture)?;
+        self.sandbox
+            .add_box_texture(ctx, path.to_string(), BoxTexture { texture, squares })
+    }
 
+    /// Loads a scene, with the images dropped onto its boxes.
+    fn load_scene(&mut self, ctx: &Ctx, snapshot: &Snapshot) -> error::Result<()> {
+        for path in snapshot
+            .objects
+            .iter()
+            .filter_map(|object| object.texture.as_ref())
         {
-            let camera = ctx.scene.root_view().camera();
-            let cp = ctx.input.scaled_cursor(CameraScaling::Expand);
-            if ctx.input.mouse_down(&MouseButton::Middle) && !self.right {
-                self.mouse_lock = cp;
-                self.camera_lock = camera.position;
+            if self.sandbox.has_box_texture(path) {
+                continue;
             }
-            if ctx.input.mouse_down(&MouseButton::Middle) {
-                let shift = vec2(
-                    (self.mouse_lock[0] - cp[0]) * camera.size.x + self.camera_lock[0],
-                    (self.mouse_lock[1] - cp[1]) * camera.size.y + self.camera_lock[1],
-                );
-                //times camera mode please
-                ctx.scene.root_view_mut().camera_mut().position = shift;
+            // A missing image leaves the box with the sandbox texture.
+            if let Err(e) = self.load_box_texture(ctx, path) {
+                self.notifications
+                    .push(Level::Warning, format!("Failed to load {path}: {e}"));
             }
-            self.right = ctx.input.mouse_down(&MouseButton::Middle);
         }
-        Ok(())
+        self.sandbox.load(ctx, snapshot)
     }
 
-    fn window(&mut self, ctx: Ctx, event: events::WindowEvent) -> Result<(), ()> {
-        match event {
-            WindowEvent::CloseRequested => ctx.exit(),
-            WindowEvent::MouseWheel(ScrollDelta::LineDelta(delta)) => {
-                let camera = ctx.scene.root_view_mut().camera_mut();
-                camera.size = camera.size - Vec2::splat(delta.y) * camera.size * 0.1;
+    /// Loads a file dropped onto the window by its extension.
+    ///
+    /// Images texture the selected box, or new boxes if nothing is selected. Fonts
+    /// are added to the label fonts, sounds are previewed and JSON files are loaded
+    /// as scenes.
+    fn handle_drop(&mut self, ctx: &Ctx, path: &Path) -> error::Result<()> {
+        let name = path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let extension = path
+            .extension()
+            .map(|extension| extension.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
+        let message = match extension.as_str() {
+            "png" | "jpg" | "jpeg" | "bmp" => {
+                if let Some(id) = self.sandbox.selected_object
+                    && !self.sandbox.is_box(id)
+                {
+                    self.notifications
+                        .push(Level::Warning, "Only boxes can be textured");
+                    return Ok(());
+                }
+                match self.sandbox.selected_object {
+                    Some(id) => {
+                        let key = path.to_string_lossy().into_owned();
+                        self.load_box_texture(ctx, &key)?;
+                        self.sandbox.texture_box(ctx, id, Some(key))?;
+                        let number = self.sandbox.number(id).unwrap_or_default();
+                        format!("Textured #{number} with {name}")
+                    }
+                    None => {
+                        let bytes = std::fs::read(path)?;
+                        let texture = load_texture(ctx, &name, bytes, image_format(path))?;
+                        self.sandbox.squares = self.square_resources.squares(ctx, texture)?;
+                        format!("New boxes use {name}")
+                    }
+                }
             }
-            _ => (),
+            "ttf" | "otf" => {
+                let font = self
+                    .labelifier
+                    .font_from_vec(std::fs::read(path)?)
+                    .map_err(SandboxError::label)?;
+                self.fonts.push((name.clone(), font));
+                self.set_font(ctx, self.fonts.len() - 1)?;
+                format!("Labels use {name}")
+            }
+            "mp3" | "ogg" | "wav" | "flac" => {
+                self.audio.play(&name, std::fs::read(path)?)?;
+                format!("Playing {name}")
+            }
+            "json" => {
+                self.load_scene(ctx, &Snapshot::read(path)?)?;
+                format!("Loaded scene {name}")
+            }
+            _ => {
+                self.notifications
+                    .push(Level::Warning, format!("Cannot open {name}"));
+                return Ok(());
+            }
+        };
+        self.notifications.push(Level::Info, message);
+        Ok(())
+    }
+
+    fn update_labels(&mut self) -> error::Result<()> {
+        for label in &mut self.labels {
+            label
+                .update_text(self.txt.clone())
+                .map_err(SandboxError::label)?;
         }
         Ok(())
     }
 
-    fn input(&mut self, ctx: Ctx, event: InputEvent) -> Result<(), ()> {
+    fn handle_input(&mut self, ctx: &Ctx, event: InputEvent) {
         if let InputEvent::KeyboardInput { input } = event {
-            match input.key {
-                Key::Named(NamedKey::Escape) => {
-                    ctx.exit();
-                }
-                Key::Named(NamedKey::F11) => {
-                    if input.state == ElementState::Released {
-                        let window = ctx.window().unwrap();
-                        window.set_fullscreen(if window.fullscreen().is_some() {
-                            None
-                        } else {
-                            Some(Fullscreen::Borderless(None))
-                        });
-                    }
+            let capturing = self.bindings.capturing();
+            let mut actions = self.bindings.key_event(&input.key, input.state);
+            // Only the console binding works while typing into egui.
+            if self.egui_typing {
+                actions.retain(|action| *action == Action::Console);
+            }
+            if capturing || !actions.is_empty() {
+                for action in actions {
+                    self.trigger(ctx, action);
                 }
-                _ => (),
+                return;
             }
             if let Some(text) = input.text
                 && let ElementState::Pressed = input.state
             {
                 if self.egui_focused {
-                    return Ok(());
+                    return;
                 };
                 match &*text {
                     "\u{8}" => {
@@ -593,46 +1091,45 @@ impl let_engine::Game for Game {
                     _ if text != "\u{7f}" => self.txt += &text,
                     _ => {}
                 }
-                self.rtext.update_text(self.txt.clone()).unwrap();
-                self.gtext.update_text(self.txt.clone()).unwrap();
-                self.btext.update_text(self.txt.clone()).unwrap();
+                if let Err(e) = self.update_labels() {
+                    self.notifications.error(e);
+                }
             }
         }
-        Ok(())
     }
 
-    fn egui(&mut self, ctx: Ctx, ectx: egui::Context) -> Result<(), ()> {
-        egui::TopBottomPanel::top("test").show(&ectx, |ui| {
+    fn draw_ui(&mut self, ctx: &Ctx, ectx: &egui::Context) {
+        let mut font = self.font;
+        egui::TopBottomPanel::top("test").show(ectx, |ui| {
+            self.tools.ui(ui, ctx, &mut self.sandbox);
             ui.horizontal(|ui| {
-                ui.checkbox(&mut self.fixed, "Anchored");
                 let mut time_scale = ctx.time.scale();
                 let response =
                     ui.add(egui::Slider::new(&mut time_scale, 0.0..=2.0).text("Time scale"));
                 if response.changed() {
                     ctx.time.set_scale(time_scale);
                 }
-                ui.add(
-                    egui::Slider::new(&mut self.object_transform.size.x, 0.01..=1.0).text("Size X"),
-                );
-                ui.add(
-                    egui::Slider::new(&mut self.object_transform.size.y, 0.01..=1.0).text("Size Y"),
-                );
-                ui.add(egui::Slider::new(&mut self.rotation, 0.0..=90.0).text("Rotation"));
-                self.object_transform.rotation = self.rotation.to_radians();
+                let mut srgba: [u8; 4] = self.color.map(|x| (x * 255.0) as u8);
+                let response = ui.color_edit_button_srgba_unmultiplied(&mut srgba);
+                if response.changed() {
+                    self.color = Color::from(srgba.map(|x| x as f32 / 255.0));
+                };
+                egui::ComboBox::from_label("Font")
+                    .selected_text(&self.fonts[self.font].0)
+                    .show_ui(ui, |ui| {
+                        for (index, (name, _)) in self.fonts.iter().enumerate() {
+                            ui.selectable_value(&mut font, index, name);
+                        }
+                    });
             });
-            let mut srgba: [u8; 4] = self.color.map(|x| (x * 255.0) as u8);
-            let response = ui.color_edit_button_srgba_unmultiplied(&mut srgba);
-            if response.changed() {
-                self.color = Color::from(srgba.map(|x| x as f32 / 255.0));
-            };
 
             ui.horizontal(|ui| {
-                let response = ui.button(if self.select { "Spawn" } else { "Select" });
-                if response.clicked() {
-                    self.select = !self.select;
-                }
-                let text = if let Some(object) = &self.selected_object {
-                    format!("Selected Object {:?}", object)
+                let number = self
+                    .sandbox
+                    .selected_object
+                    .and_then(|id| self.sandbox.number(id));
+                let text = if let Some(number) = number {
+                    format!("Selected Object #{number}")
                 } else {
                     "Selected None".to_string()
                 };
@@ -649,12 +1146,390 @@ impl let_engine::Game for Game {
                 };
             });
 
-            ui.label(egui::RichText::new(format!("FPS: {}", ctx.time.fps(),)).monospace());
+            ui.horizontal(|ui| {
+                ui.label(egui::RichText::new(format!("FPS: {}", ctx.time.fps(),)).monospace());
+                self.panels.toggles(ui);
+            });
+        });
+
+        if font != self.font
+            && let Err(e) = self.set_font(ctx, font)
+        {
+            self.notifications.error(e);
+        }
+
+        let notifications = &mut self.notifications;
+        self.panels
+            .show(ectx, Panel::Benchmark, notifications, |ui| {
+                match self.benchmark.ui(ui) {
+                    BenchmarkAction::Start => return self.benchmark.start(ctx, &mut self.sandbox),
+                    BenchmarkAction::Cancel => self.benchmark.cancel(ctx, &mut self.sandbox),
+                    BenchmarkAction::None => (),
+                }
+                Ok(())
+            });
+        self.panels
+            .show(ectx, Panel::Performance, notifications, |ui| {
+                self.profiler.ui(ui);
+                Ok(())
+            });
+        self.profiler
+            .record_frame(self.sandbox.spawned_objects.len());
+        self.panels
+            .show(ectx, Panel::Bindings, notifications, |ui| {
+                self.bindings.ui(ui, ctx);
+                Ok(())
+            });
+        self.panels.show(ectx, Panel::Gamepad, notifications, |ui| {
+            self.gamepad.ui(ui);
+            Ok(())
+        });
+        self.log_console
+            .show(ectx, self.panels.open_mut(Panel::Log));
+        self.panels.show(ectx, Panel::Script, notifications, |ui| {
+            self.scripting.ui(ui)
+        });
+        self.panels.show(ectx, Panel::Physics, notifications, |ui| {
+            self.physics.ui(ui, ctx)
+        });
+        self.panels.show(ectx, Panel::Fields, notifications, |ui| {
+            fields::ui(ui, ctx, &mut self.sandbox)
+        });
+        self.panels
+            .show(ectx, Panel::Collisions, notifications, |ui| {
+                self.collisions.ui(ui);
+                Ok(())
+            });
+        self.panels.show(ectx, Panel::Groups, notifications, |ui| {
+            groups::ui(ui, ctx, &mut self.sandbox)
+        });
+        self.panels
+            .show(ectx, Panel::Materials, notifications, |ui| {
+                materials::ui(ui, ctx, &mut self.sandbox)
+            });
+        self.panels.show(ectx, Panel::Motors, notifications, |ui| {
+            self.motor_editor
+                .ui(ui, &mut self.sandbox, self.bindings.held_keys());
+            Ok(())
         });
+        self.panels.show(ectx, Panel::Assets, notifications, |ui| {
+            self.asset_inspector.ui(ui);
+            Ok(())
+        });
+
+        if let Some(command) = self.console.show(ectx) {
+            let output = self
+                .run_command(ctx, command)
+                .unwrap_or_else(|e| format!("error: {e}"));
+            self.console.print(&output);
+        }
+
+        self.notifications.show(ectx);
+
+        self.egui_typing = ectx.wants_keyboard_input();
         self.egui_focused =
-            ectx.is_pointer_over_area() || ectx.is_using_pointer() || ectx.wants_keyboard_input();
+            ectx.is_pointer_over_area() || ectx.is_using_pointer() || self.egui_typing;
+    }
+}
+
+impl let_engine::Game for Game {
+    fn update(&mut self, ctx: Ctx) -> Result<(), ()> {
+        if self.crash.is_some() {
+            return Ok(());
+        }
+        self.guard("update", &ctx, |game| {
+            if let Err(e) = game.tick(&ctx) {
+                game.notifications.error(e);
+            }
+        });
+        Ok(())
+    }
+
+    fn window(&mut self, ctx: Ctx, event: events::WindowEvent) -> Result<(), ()> {
+        self.input_log.record(&event);
+        if let WindowEvent::CloseRequested = event {
+            ctx.exit();
+        }
+        if self.crash.is_some() {
+            return Ok(());
+        }
+        self.guard("window", &ctx, |game| match event {
+            WindowEvent::MouseWheel(ScrollDelta::LineDelta(delta)) => {
+                let camera = ctx.scene.root_view_mut().camera_mut();
+                camera.size = camera.size - Vec2::splat(delta.y) * camera.size * 0.1;
+            }
+            WindowEvent::DroppedFile(path) => {
+                if let Err(e) = game.handle_drop(&ctx, &path) {
+                    game.notifications.error(e);
+                }
+            }
+            _ => (),
+        });
+        Ok(())
+    }
+
+    fn input(&mut self, ctx: Ctx, event: InputEvent) -> Result<(), ()> {
+        self.input_log.record(&event);
+        if self.crash.is_none() {
+            self.guard("input", &ctx, |game| game.handle_input(&ctx, event));
+        }
         Ok(())
     }
+
+    fn egui(&mut self, ctx: Ctx, ectx: egui::Context) -> Result<(), ()> {
+        if let Some(crash) = &self.crash {
+            match crash.ui(&ectx) {
+                CrashAction::Continue => self.crash = None,
+                CrashAction::Quit => ctx.exit(),
+                CrashAction::None => (),
+            }
+            return Ok(());
+        }
+        let start = Instant::now();
+        self.guard("egui", &ctx, |game| game.draw_ui(&ctx, &ectx));
+        self.benchmark.record_frame(&ctx, start);
+        Ok(())
+    }
+}
+
+/// GPU resources of the textured appearances, kept to rebuild them with a reloaded texture.
+struct SquareResources {
+    material: MaterialId<TVert>,
+    model: ModelId<TVert>,
+    disc: ModelId<TVert>,
+    /// The tint of each collision group.
+    color_buffers: Vec<BufferId<Color>>,
+    /// Tints of empty and occupied sensors.
+    sensor_colors: [BufferId<Color>; 2],
+}
+
+impl SquareResources {
+    /// The box appearance of every collision group.
+    fn squares(
+        &self,
+        ctx: &Ctx,
+        texture: TextureId,
+    ) -> error::Result<Vec<Appearance<VulkanTypes>>> {
+        self.color_buffers
+            .iter()
+            .map(|&color_buffer| self.build(ctx, self.model, texture, color_buffer))
+            .collect()
+    }
+
+    /// Every appearance drawn with the texture.
+    fn textured(&self, ctx: &Ctx, texture: TextureId) -> error::Result<Textured> {
+        let [sensor, sensor_occupied] = self.sensor_colors;
+        Ok(Textured {
+            texture,
+            squares: self.squares(ctx, texture)?,
+            sensor: self.build(ctx, self.model, texture, sensor)?,
+            sensor_occupied: self.build(ctx, self.model, texture, sensor_occupied)?,
+            circle: self.build(ctx, self.disc, texture, self.color_buffers[0])?,
+        })
+    }
+
+    fn build(
+        &self,
+        ctx: &Ctx,
+        model: ModelId<TVert>,
+        texture: TextureId,
+        color_buffer: BufferId<Color>,
+    ) -> error::Result<Appearance<VulkanTypes>> {
+        AppearanceBuilder::default()
+            .model(model)
+            .material(self.material)
+            .descriptors(&[
+                (Location::new(0, 0), Descriptor::Mvp),
+                (Location::new(1, 0), Descriptor::buffer(color_buffer)),
+                (Location::new(2, 0), Descriptor::Texture(texture)),
+            ])
+            .build(&ctx.gpu)
+            .map_err(SandboxError::gpu)
+    }
+}
+
+/// Translucent zones in one color per field kind, and a white arrow.
+fn field_style(ctx: &Ctx, line_material: MaterialId<Vec2>) -> error::Result<fields::FieldStyle> {
+    let fill_material = ctx
+        .gpu
+        .load_material::<Vec2>(&Material::new(
+            MaterialSettingsBuilder::default()
+                .topology(Topology::TriangleList)
+                .build()
+                .map_err(SandboxError::gpu)?,
+            GraphicsShaders::new_default(),
+        ))
+        .map_err(SandboxError::gpu)?;
+    let rect_model = ctx
+        .gpu
+        .load_model(&Model::new(
+            vec![
+                vec2(-1.0, -1.0),
+                vec2(1.0, -1.0),
+                vec2(1.0, 1.0),
+                vec2(1.0, 1.0),
+                vec2(-1.0, 1.0),
+                vec2(-1.0, -1.0),
+            ],
+            BufferAccess::Fixed,
+        ))
+        .map_err(SandboxError::gpu)?;
+    let circle_model = ctx
+        .gpu
+        .load_model(&Model::new(
+            (0..32)
+                .flat_map(|i| {
+                    let angle = |i: i32| Vec2::from_angle(i as f32 / 32.0 * std::f32::consts::TAU);
+                    [Vec2::ZERO, angle(i), angle(i + 1)]
+                })
+                .collect(),
+            BufferAccess::Fixed,
+        ))
+        .map_err(SandboxError::gpu)?;
+    let arrow_model = ctx
+        .gpu
+        .load_model(&model!(
+            vec![
+                vec2(0.0, 0.0),
+                vec2(1.0, 0.0),
+                vec2(0.85, 0.08),
+                vec2(0.85, -0.08),
+            ],
+            vec![0, 1, 2, 3, 1]
+        ))
+        .map_err(SandboxError::gpu)?;
+
+    let appearance = |material, model, color| -> error::Result<Appearance<VulkanTypes>> {
+        let color = ctx
+            .gpu
+            .load_buffer(&Buffer::from_data(
+                BufferUsage::Uniform,
+                BufferAccess::Fixed,
+                color,
+            ))
+            .map_err(SandboxError::gpu)?;
+        AppearanceBuilder::default()
+            .material(material)
+            .model(model)
+            .descriptors(&[
+                (Location::new(0, 0), Descriptor::Mvp),
+                (Location::new(1, 0), Descriptor::buffer(color)),
+            ])
+            .build(&ctx.gpu)
+            .map_err(SandboxError::gpu)
+    };
+    Ok(fields::FieldStyle {
+        wind: appearance(
+            fill_material,
+            rect_model,
+            Color::from_rgba(0.3, 0.6, 1.0, 0.2),
+        )?,
+        attractor: appearance(
+            fill_material,
+            circle_model,
+            Color::from_rgba(0.8, 0.3, 1.0, 0.2),
+        )?,
+        planet: appearance(
+            fill_material,
+            circle_model,
+            Color::from_rgba(1.0, 0.6, 0.2, 0.2),
+        )?,
+        arrow: appearance(
+            line_material,
+            arrow_model,
+            Color::from_rgba(1.0, 1.0, 1.0, 0.8),
+        )?,
+    })
+}
+
+/// Decodes and uploads a texture.
+/// The format of an image file by its extension.
+fn image_format(path: &Path) -> ImageFormat {
+    let extension = path
+        .extension()
+        .map(|extension| extension.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    match extension.as_str() {
+        "png" => ImageFormat::Png,
+        "bmp" => ImageFormat::Bmp,
+        _ => ImageFormat::Jpeg,
+    }
+}
+
+fn load_texture(
+    ctx: &Ctx,
+    path: &str,
+    bytes: Vec<u8>,
+    format: ImageFormat,
+) -> error::Result<TextureId> {
+    ctx.gpu
+        .load_texture(
+            &Texture::from_bytes(
+                bytes,
+                format,
+                TextureSettingsBuilder::default()
+                    .format(Format::Rgba8Unorm)
+                    .build()
+                    .map_err(SandboxError::gpu)?,
+            )
+            .map_err(SandboxError::asset(path))?,
+        )
+        .map_err(SandboxError::gpu)
+}
+
+/// The red, green and blue labels showing `text` with different alignments.
+fn create_labels(
+    ctx: &Ctx,
+    labelifier: &mut Labelifier<VulkanTypes>,
+    font: Font,
+    text: &str,
+) -> error::Result<[Label<VulkanTypes>; 3]> {
+    let fsize = 35.0;
+    let label = |labelifier: &mut Labelifier<VulkanTypes>, text_color, align| {
+        Label::new(
+            LabelCreateInfo {
+                transform: Transform::with_size(Vec2::splat(0.001)),
+                text_color,
+                text: text.to_string(),
+                scale: Vec2::splat(fsize),
+                extent: UVec2::splat(2000),
+                font,
+                align,
+            },
+            labelifier,
+            &ctx.gpu,
+        )
+        .map_err(SandboxError::label)
+    };
+    Ok([
+        label(
+            labelifier,
+            Color::from_rgba(1.0, 0.0, 0.0, 1.0),
+            Direction::Nw,
+        )?,
+        label(
+            labelifier,
+            Color::from_rgba(0.0, 1.0, 0.0, 1.0),
+            Direction::Center,
+        )?,
+        label(
+            labelifier,
+            Color::from_rgba(0.0, 0.0, 1.0, 1.0),
+            Direction::So,
+        )?,
+    ])
+}
+
+/// The assets directory exported by the `debug` script, or `assets` in the working directory.
+fn assets_dir() -> PathBuf {
+    std::env::var_os("ASSETS_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from("assets"))
+}
+
+/// Where the console saves and loads scenes.
+fn scene_path(name: &str) -> PathBuf {
+    PathBuf::from("scenes").join(format!("{name}.json"))
 }
 
 fn angle_between(x: Vec2, y: Vec2) -> f32 {
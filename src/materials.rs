@@ -0,0 +1,358 @@
+//! Physical materials: friction, restitution and density of spawned boxes.
+//!
+//! The built in presets are always available. Custom presets are saved in
+//! `material_presets.toml` in the assets directory.
+
+use std::{collections::BTreeMap, fs, path::PathBuf};
+
+use let_engine::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    Ctx, assets_dir,
+    error::{ObjectLookup, Result},
+    sandbox::Sandbox,
+};
+
+/// How the coefficients of two touching colliders are combined.
+///
+/// When the colliders disagree, the rule later in this list wins.
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CombineRule {
+    Average,
+    Min,
+    Multiply,
+    Max,
+}
+
+impl CombineRule {
+    const ALL: [CombineRule; 4] = [
+        CombineRule::Average,
+        CombineRule::Min,
+        CombineRule::Multiply,
+        CombineRule::Max,
+    ];
+
+    fn name(self) -> &'static str {
+        match self {
+            CombineRule::Average => "Average",
+            CombineRule::Min => "Min",
+            CombineRule::Multiply => "Multiply",
+            CombineRule::Max => "Max",
+        }
+    }
+
+    fn rule(self) -> CoefficientCombineRule {
+        match self {
+            CombineRule::Average => CoefficientCombineRule::Average,
+            CombineRule::Min => CoefficientCombineRule::Min,
+            CombineRule::Multiply => CoefficientCombineRule::Multiply,
+            CombineRule::Max => CoefficientCombineRule::Max,
+        }
+    }
+
+    fn from_rule(rule: CoefficientCombineRule) -> Self {
+        match rule {
+            CoefficientCombineRule::Min => CombineRule::Min,
+            CoefficientCombineRule::Multiply => CombineRule::Multiply,
+            CoefficientCombineRule::Max => CombineRule::Max,
+            _ => CombineRule::Average,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct PhysicalMaterial {
+    pub friction: f32,
+    pub restitution: f32,
+    pub density: f32,
+    pub friction_combine: CombineRule,
+    pub restitution_combine: CombineRule,
+}
+
+impl Default for PhysicalMaterial {
+    fn default() -> Self {
+        Self::BUILTIN[0].1
+    }
+}
+
+impl PhysicalMaterial {
+    /// Built in presets, by name.
+    pub const BUILTIN: [(&'static str, PhysicalMaterial); 6] = [
+        // What boxes were always spawned with: no bounce, even against bouncy surfaces.
+        (
+            "Default",
+            PhysicalMaterial {
+                friction: 0.5,
+                restitution: 0.0,
+                density: 1.0,
+                friction_combine: CombineRule::Average,
+                restitution_combine: CombineRule::Min,
+            },
+        ),
+        (
+            "Ice",
+            PhysicalMaterial {
+                friction: 0.02,
+                restitution: 0.05,
+                density: 0.92,
+                friction_combine: CombineRule::Min,
+                restitution_combine: CombineRule::Average,
+            },
+        ),
+        (
+            "Rubber",
+            PhysicalMaterial {
+                friction: 1.0,
+                restitution: 0.6,
+                density: 1.1,
+                friction_combine: CombineRule::Max,
+                restitution_combine: CombineRule::Average,
+            },
+        ),
+        (
+            "Wood",
+            PhysicalMaterial {
+                friction: 0.5,
+                restitution: 0.3,
+                density: 0.6,
+                friction_combine: CombineRule::Average,
+                restitution_combine: CombineRule::Average,
+            },
+        ),
+        (
+            "Steel",
+            PhysicalMaterial {
+                friction: 0.4,
+                restitution: 0.2,
+                density: 7.8,
+                friction_combine: CombineRule::Average,
+                restitution_combine: CombineRule::Average,
+            },
+        ),
+        (
+            "Bouncy ball",
+            PhysicalMaterial {
+                friction: 0.8,
+                restitution: 0.95,
+                density: 0.3,
+                friction_combine: CombineRule::Average,
+                restitution_combine: CombineRule::Max,
+            },
+        ),
+    ];
+
+    /// Sets the material on a collider builder.
+    pub fn build(self, builder: ColliderBuilder) -> ColliderBuilder {
+        builder
+            .friction(self.friction)
+            .restitution(self.restitution)
+            .density(self.density)
+            .friction_combine_rule(self.friction_combine.rule())
+            .restitution_combine_rule(self.restitution_combine.rule())
+    }
+
+    /// Changes the material of an existing collider. The mass follows the new density.
+    pub fn apply(self, collider: &mut Collider) {
+        collider.set_friction(self.friction);
+        collider.set_restitution(self.restitution);
+        collider.set_density(self.density);
+        collider.set_friction_combine_rule(self.friction_combine.rule());
+        collider.set_restitution_combine_rule(self.restitution_combine.rule());
+    }
+
+    pub fn of(collider: &Collider) -> Self {
+        Self {
+            friction: collider.friction(),
+            restitution: collider.restitution(),
+            density: collider.density(),
+            friction_combine: CombineRule::from_rule(collider.friction_combine_rule()),
+            restitution_combine: CombineRule::from_rule(collider.restitution_combine_rule()),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Default)]
+struct PresetsFile {
+    presets: BTreeMap<String, PhysicalMaterial>,
+}
+
+/// The built in and custom presets, and the one being edited in the materials window.
+pub struct MaterialLibrary {
+    custom: BTreeMap<String, PhysicalMaterial>,
+    editing: PhysicalMaterial,
+    preset_name: String,
+    status: String,
+}
+
+impl MaterialLibrary {
+    pub fn new() -> Self {
+        let mut library = Self {
+            custom: BTreeMap::new(),
+            editing: PhysicalMaterial::default(),
+            preset_name: String::new(),
+            status: String::new(),
+        };
+        if let Ok(file) = fs::read_to_string(Self::path()) {
+            match toml::from_str::<PresetsFile>(&file) {
+                Ok(file) => library.custom = file.presets,
+                Err(e) => {
+                    library.status = format!("Failed to parse material presets: {e}");
+                    log::warn!("{}", library.status);
+                }
+            }
+        }
+        library
+    }
+
+    fn path() -> PathBuf {
+        assets_dir().join("material_presets.toml")
+    }
+
+    fn save(&mut self) {
+        let file = PresetsFile {
+            presets: self.custom.clone(),
+        };
+        self.status = match toml::to_string_pretty(&file)
+            .map_err(|e| e.to_string())
+            .and_then(|file| fs::write(Self::path(), file).map_err(|e| e.to_string()))
+        {
+            Ok(()) => format!("Saved to {}", Self::path().display()),
+            Err(e) => format!("Failed to save material presets: {e}"),
+        };
+    }
+
+    /// Every preset, built in ones first.
+    pub fn presets(&self) -> impl Iterator<Item = (&str, PhysicalMaterial)> {
+        PhysicalMaterial::BUILTIN.into_iter().chain(
+            self.custom
+                .iter()
+                .map(|(name, &material)| (name.as_str(), material)),
+        )
+    }
+
+    /// The name of the first preset with exactly these values.
+    pub fn name_of(&self, material: PhysicalMaterial) -> Option<&str> {
+        self.presets()
+            .find(|(_, preset)| *preset == material)
+            .map(|(name, _)| name)
+    }
+
+    /// A combo box picking one of the presets.
+    pub fn picker(&self, ui: &mut egui::Ui, id: &str, material: &mut PhysicalMaterial) {
+        egui::ComboBox::from_id_salt(id)
+            .selected_text(self.name_of(*material).unwrap_or("Custom"))
+            .show_ui(ui, |ui| {
+                for (name, preset) in self.presets() {
+                    ui.selectable_value(material, preset, name);
+                }
+            });
+    }
+}
+
+/// The materials window: a preset editor and the selected object's material.
+pub fn ui(ui: &mut egui::Ui, ctx: &Ctx, sandbox: &mut Sandbox) -> Result<()> {
+    let library = &mut sandbox.materials;
+    let mut save = false;
+
+    let mut editing = library.editing;
+    ui.horizontal(|ui| {
+        library.picker(ui, "material_preset", &mut editing);
+        ui.add(egui::TextEdit::singleline(&mut library.preset_name).hint_text("Preset name"));
+        let name = library.preset_name.trim().to_string();
+        let builtin = PhysicalMaterial::BUILTIN.iter().any(|(n, _)| *n == name);
+        if ui
+            .add_enabled(
+                !name.is_empty() && !builtin,
+                egui::Button::new("Save preset"),
+            )
+            .clicked()
+        {
+            library.custom.insert(name.clone(), editing);
+            save = true;
+        }
+        if ui
+            .add_enabled(
+                library.custom.contains_key(&name),
+                egui::Button::new("Delete preset"),
+            )
+            .clicked()
+        {
+            library.custom.remove(&name);
+            save = true;
+        }
+    });
+    if save {
+        library.save();
+    }
+    if !library.status.is_empty() {
+        ui.label(&library.status);
+    }
+
+    egui::Grid::new("material").show(ui, |ui| {
+        ui.label("Friction");
+        ui.add(
+            egui::DragValue::new(&mut editing.friction)
+                .speed(0.01)
+                .range(0.0..=10.0),
+        );
+        ui.end_row();
+        ui.label("Restitution");
+        ui.add(
+            egui::DragValue::new(&mut editing.restitution)
+                .speed(0.01)
+                .range(0.0..=1.0),
+        );
+        ui.end_row();
+        ui.label("Density");
+        ui.add(
+            egui::DragValue::new(&mut editing.density)
+                .speed(0.01)
+                .range(0.01..=100.0),
+        );
+        ui.end_row();
+        let rules = [
+            ("Friction combine", &mut editing.friction_combine),
+            ("Restitution combine", &mut editing.restitution_combine),
+        ];
+        for (name, rule) in rules {
+            ui.label(name);
+            egui::ComboBox::from_id_salt(name)
+                .selected_text(rule.name())
+                .show_ui(ui, |ui| {
+                    for option in CombineRule::ALL {
+                        ui.selectable_value(rule, option, option.name());
+                    }
+                });
+            ui.end_row();
+        }
+    });
+    library.editing = editing;
+    ui.separator();
+
+    let Some(id) = sandbox.selected_object else {
+        ui.label("Select an object to change its material.");
+        return Ok(());
+    };
+    let number = sandbox.number(id).unwrap_or_default();
+    let object = ctx.scene.object_mut(id).or_stale(id)?;
+    let Some(collider) = object.collider_mut() else {
+        ui.label(format!("Object #{number} has no collider."));
+        return Ok(());
+    };
+    let current = PhysicalMaterial::of(collider);
+    let name = sandbox.materials.name_of(current).unwrap_or("custom");
+    let mut edit = false;
+    ui.horizontal(|ui| {
+        ui.label(format!("Object #{number} is {name}"));
+        if ui.button("Apply edited material").clicked() {
+            editing.apply(collider);
+        }
+        edit = ui.button("Edit").clicked();
+    });
+    if edit {
+        sandbox.materials.editing = current;
+    }
+    Ok(())
+}
@@ -0,0 +1,153 @@
+//! Joint motors driven by held keys, and the window assigning them.
+
+use std::collections::HashSet;
+
+use serde::{Deserialize, Serialize};
+
+use crate::sandbox::Sandbox;
+
+/// Drives a revolute or wheel joint while one of its keys is held.
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+pub struct Motor {
+    /// Target angular velocity in radians per second. Positive turns clockwise on screen.
+    pub speed: f32,
+    pub max_torque: f32,
+    /// Key names as used in `bindings.toml`.
+    pub forward: Option<String>,
+    pub backward: Option<String>,
+}
+
+impl Default for Motor {
+    fn default() -> Self {
+        Self {
+            speed: 20.0,
+            max_torque: 0.05,
+            forward: None,
+            backward: None,
+        }
+    }
+}
+
+impl Motor {
+    /// 1 while only the forward key is held, -1 for the backward key, 0 otherwise.
+    pub fn direction(&self, held_keys: &HashSet<String>) -> f32 {
+        let held = |key: &Option<String>| key.as_ref().is_some_and(|key| held_keys.contains(key));
+        match (held(&self.forward), held(&self.backward)) {
+            (true, false) => 1.0,
+            (false, true) => -1.0,
+            _ => 0.0,
+        }
+    }
+}
+
+/// Which key of which joint is waiting for a key press.
+#[derive(Clone, Copy, PartialEq)]
+struct Capture {
+    joint: usize,
+    forward: bool,
+}
+
+/// The motors window, listing every joint that can take a motor.
+pub struct MotorEditor {
+    capturing: Option<Capture>,
+    /// Keys held when the capture started, ignored until released.
+    capture_held: HashSet<String>,
+}
+
+impl MotorEditor {
+    pub fn new() -> Self {
+        Self {
+            capturing: None,
+            capture_held: HashSet::new(),
+        }
+    }
+
+    pub fn ui(&mut self, ui: &mut egui::Ui, sandbox: &mut Sandbox, held_keys: &HashSet<String>) {
+        self.capture_held.retain(|key| held_keys.contains(key));
+        if let Some(capture) = self.capturing
+            && let Some(key) = held_keys.difference(&self.capture_held).next()
+        {
+            if let Some(motor) = sandbox
+                .joints
+                .get_mut(capture.joint)
+                .and_then(|joint| joint.motor.as_mut())
+            {
+                let slot = if capture.forward {
+                    &mut motor.forward
+                } else {
+                    &mut motor.backward
+                };
+                *slot = Some(key.clone());
+            }
+            self.capturing = None;
+        }
+
+        let numbers: Vec<(u32, u32)> = sandbox
+            .joints
+            .iter()
+            .map(|joint| {
+                (
+                    sandbox.number(joint.a).unwrap_or_default(),
+                    sandbox.number(joint.b).unwrap_or_default(),
+                )
+            })
+            .collect();
+        let mut any = false;
+        egui::Grid::new("motors").striped(true).show(ui, |ui| {
+            for (index, joint) in sandbox.joints.iter_mut().enumerate() {
+                if !joint.kind.motorized() {
+                    continue;
+                }
+                any = true;
+                let (a, b) = numbers[index];
+                let mut enabled = joint.motor.is_some();
+                ui.checkbox(&mut enabled, format!("#{a} - #{b}"));
+                if enabled != joint.motor.is_some() {
+                    joint.motor = enabled.then(Motor::default);
+                }
+                let Some(motor) = &mut joint.motor else {
+                    ui.end_row();
+                    continue;
+                };
+                ui.add(
+                    egui::DragValue::new(&mut motor.speed)
+                        .speed(0.1)
+                        .suffix(" rad/s"),
+                );
+                ui.add(
+                    egui::DragValue::new(&mut motor.max_torque)
+                        .speed(0.001)
+                        .range(0.0..=f32::MAX)
+                        .prefix("torque "),
+                );
+                for forward in [true, false] {
+                    let capture = Capture {
+                        joint: index,
+                        forward,
+                    };
+                    let (direction, key) = if forward {
+                        ("Forward", &motor.forward)
+                    } else {
+                        ("Backward", &motor.backward)
+                    };
+                    let capturing = self.capturing == Some(capture);
+                    let text = if capturing {
+                        "Press a key...".to_string()
+                    } else {
+                        format!("{direction}: {}", key.as_deref().unwrap_or("none"))
+                    };
+                    if ui.button(text).clicked() {
+                        self.capturing = (!capturing).then_some(capture);
+                        self.capture_held = held_keys.clone();
+                    }
+                }
+                ui.end_row();
+            }
+        });
+        if !any {
+            ui.label(
+                "No revolute or wheel joints. Build a vehicle or join objects in the console.",
+            );
+        }
+    }
+}
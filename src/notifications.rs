@@ -0,0 +1,109 @@
+use std::time::{Duration, Instant};
+
+use egui::{Align2, Color32, RichText};
+
+use crate::error::SandboxError;
+
+/// How long a toast stays on screen.
+const LIFETIME: Duration = Duration::from_secs(6);
+const MAX_TOASTS: usize = 8;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Level {
+    Info,
+    Warning,
+    Error,
+}
+
+struct Toast {
+    level: Level,
+    message: String,
+    created: Instant,
+    /// How often the same message was pushed again while this toast was shown.
+    repeats: u32,
+}
+
+/// Toast notifications in the bottom right corner.
+pub struct Notifications {
+    toasts: Vec<Toast>,
+}
+
+impl Notifications {
+    pub fn new() -> Self {
+        Self { toasts: Vec::new() }
+    }
+
+    /// Shows a toast and logs the message.
+    ///
+    /// A message that is still on screen is counted on its toast instead, so errors
+    /// repeating every tick neither flood the toasts nor the log.
+    pub fn push(&mut self, level: Level, message: impl Into<String>) {
+        let message = message.into();
+        if let Some(toast) = self.toasts.iter_mut().find(|toast| {
+            toast.level == level && toast.message == message && toast.created.elapsed() < LIFETIME
+        }) {
+            toast.repeats += 1;
+            toast.created = Instant::now();
+            return;
+        }
+        let log_level = match level {
+            Level::Info => log::Level::Info,
+            Level::Warning => log::Level::Warn,
+            Level::Error => log::Level::Error,
+        };
+        log::log!(log_level, "{message}");
+        if self.toasts.len() == MAX_TOASTS {
+            self.toasts.remove(0);
+        }
+        self.toasts.push(Toast {
+            level,
+            message,
+            created: Instant::now(),
+            repeats: 0,
+        });
+    }
+
+    /// Shows a recoverable error to the user.
+    pub fn error(&mut self, error: SandboxError) {
+        self.push(Level::Error, error.to_string());
+    }
+
+    pub fn show(&mut self, ectx: &egui::Context) {
+        self.toasts
+            .retain(|toast| toast.created.elapsed() < LIFETIME);
+        if self.toasts.is_empty() {
+            return;
+        }
+        let mut dismissed = None;
+        egui::Area::new(egui::Id::new("notifications"))
+            .anchor(Align2::RIGHT_BOTTOM, egui::vec2(-10.0, -10.0))
+            .show(ectx, |ui| {
+                for (index, toast) in self.toasts.iter().enumerate() {
+                    let color = match toast.level {
+                        Level::Info => Color32::LIGHT_BLUE,
+                        Level::Warning => Color32::YELLOW,
+                        Level::Error => Color32::LIGHT_RED,
+                    };
+                    let response = egui::Frame::popup(ui.style())
+                        .show(ui, |ui| {
+                            ui.set_max_width(400.0);
+                            let text = match toast.repeats {
+                                0 => toast.message.clone(),
+                                repeats => format!("{} (x{})", toast.message, repeats + 1),
+                            };
+                            ui.label(RichText::new(text).color(color));
+                        })
+                        .response
+                        .interact(egui::Sense::click());
+                    if response.clicked() {
+                        dismissed = Some(index);
+                    }
+                }
+            });
+        if let Some(index) = dismissed {
+            self.toasts.remove(index);
+        }
+        // Keep repainting so toasts disappear on time.
+        ectx.request_repaint_after(Duration::from_millis(250));
+    }
+}
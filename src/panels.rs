@@ -0,0 +1,109 @@
+//! The tool windows opened from the top bar.
+
+use crate::{error::Result, notifications::Notifications};
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Panel {
+    Benchmark,
+    Performance,
+    Bindings,
+    Gamepad,
+    Log,
+    Script,
+    Assets,
+    Physics,
+    Fields,
+    Collisions,
+    Groups,
+    Materials,
+    Motors,
+}
+
+impl Panel {
+    /// Every panel, in the order of the top bar.
+    pub const ALL: [Panel; 13] = [
+        Panel::Benchmark,
+        Panel::Performance,
+        Panel::Bindings,
+        Panel::Gamepad,
+        Panel::Log,
+        Panel::Script,
+        Panel::Assets,
+        Panel::Physics,
+        Panel::Fields,
+        Panel::Collisions,
+        Panel::Groups,
+        Panel::Materials,
+        Panel::Motors,
+    ];
+
+    pub fn title(self) -> &'static str {
+        match self {
+            Panel::Benchmark => "Benchmark",
+            Panel::Performance => "Performance",
+            Panel::Bindings => "Bindings",
+            Panel::Gamepad => "Gamepad",
+            Panel::Log => "Log",
+            Panel::Script => "Script",
+            Panel::Assets => "Assets",
+            Panel::Physics => "Physics",
+            Panel::Fields => "Fields",
+            Panel::Collisions => "Collisions",
+            Panel::Groups => "Groups",
+            Panel::Materials => "Materials",
+            Panel::Motors => "Motors",
+        }
+    }
+
+    /// Panels with long tables start out larger than their content.
+    fn default_size(self) -> Option<[f32; 2]> {
+        match self {
+            Panel::Collisions => Some([450.0, 300.0]),
+            Panel::Assets => Some([600.0, 400.0]),
+            _ => None,
+        }
+    }
+}
+
+/// Which panels are open.
+pub struct Panels {
+    open: [bool; Panel::ALL.len()],
+}
+
+impl Panels {
+    pub fn new() -> Self {
+        Self {
+            open: [false; Panel::ALL.len()],
+        }
+    }
+
+    pub fn open_mut(&mut self, panel: Panel) -> &mut bool {
+        &mut self.open[panel as usize]
+    }
+
+    /// A toggle for every panel.
+    pub fn toggles(&mut self, ui: &mut egui::Ui) {
+        for panel in Panel::ALL {
+            ui.toggle_value(self.open_mut(panel), panel.title());
+        }
+    }
+
+    /// Shows the panel's window if it is open. Errors of its contents become toasts.
+    pub fn show(
+        &mut self,
+        ectx: &egui::Context,
+        panel: Panel,
+        notifications: &mut Notifications,
+        add_contents: impl FnOnce(&mut egui::Ui) -> Result<()>,
+    ) {
+        let mut window = egui::Window::new(panel.title()).open(self.open_mut(panel));
+        if let Some(size) = panel.default_size() {
+            window = window.default_size(size);
+        }
+        window.show(ectx, |ui| {
+            if let Err(e) = add_contents(ui) {
+                notifications.error(e);
+            }
+        });
+    }
+}
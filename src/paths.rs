@@ -0,0 +1,205 @@
+//! Kinematic bodies following paths of waypoints.
+//!
+//! A body on a path is driven by velocity rather than teleported, so dynamic bodies
+//! resting on it are carried along by friction.
+
+use let_engine::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// The most waypoints a path can have.
+pub const MAX_WAYPOINTS: usize = 32;
+
+/// What happens after the last waypoint.
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PathMode {
+    /// Goes back to the first waypoint and starts over.
+    Loop,
+    /// Travels the waypoints in reverse, then forward again.
+    PingPong,
+}
+
+/// The waypoints a kinematic body travels, in world space.
+#[derive(Clone)]
+pub struct Path {
+    pub waypoints: Vec<Vec2>,
+    pub mode: PathMode,
+    /// Units per second.
+    pub speed: f32,
+    /// Whether the body was fixed before it followed the path, restored when the path
+    /// is removed.
+    pub fixed: bool,
+    /// Index of the waypoint the body is heading to.
+    target: usize,
+    forward: bool,
+}
+
+/// A path in a scene file.
+#[derive(Serialize, Deserialize)]
+pub struct PathSnapshot {
+    pub waypoints: Vec<[f32; 2]>,
+    pub mode: PathMode,
+    pub speed: f32,
+}
+
+impl Path {
+    pub fn new(waypoints: Vec<Vec2>, mode: PathMode, speed: f32) -> Self {
+        Self {
+            waypoints,
+            mode,
+            speed,
+            fixed: false,
+            target: 0,
+            forward: true,
+        }
+    }
+
+    pub fn from_snapshot(snapshot: &PathSnapshot) -> Self {
+        let waypoints = snapshot
+            .waypoints
+            .iter()
+            .take(MAX_WAYPOINTS)
+            .map(|&point| Vec2::from_array(point))
+            .collect();
+        Self::new(waypoints, snapshot.mode, snapshot.speed)
+    }
+
+    pub fn snapshot(&self) -> PathSnapshot {
+        PathSnapshot {
+            waypoints: self
+                .waypoints
+                .iter()
+                .map(|point| point.to_array())
+                .collect(),
+            mode: self.mode,
+            speed: self.speed,
+        }
+    }
+
+    /// The velocity taking a body at `position` along the path for one tick.
+    ///
+    /// Arriving at a waypoint lands on it exactly and heads for the next one.
+    pub fn velocity(&mut self, position: Vec2, dt: f32) -> Vec2 {
+        let Some(&target) = self.waypoints.get(self.target) else {
+            return Vec2::ZERO;
+        };
+        let offset = target - position;
+        if offset.length() > self.speed * dt {
+            return offset.normalize_or_zero() * self.speed;
+        }
+        self.advance();
+        offset / dt
+    }
+
+    fn advance(&mut self) {
+        let last = self.waypoints.len() - 1;
+        if last == 0 {
+            return;
+        }
+        match self.mode {
+            PathMode::Loop => self.target = (self.target + 1) % self.waypoints.len(),
+            PathMode::PingPong => {
+                if self.target == last {
+                    self.forward = false;
+                } else if self.target == 0 {
+                    self.forward = true;
+                }
+                self.target = if self.forward {
+                    self.target + 1
+                } else {
+                    self.target - 1
+                };
+            }
+        }
+    }
+
+    /// The points of the line drawn for the path, closed for loops.
+    pub fn outline(&self) -> Vec<Vec2> {
+        let mut points = self.waypoints.clone();
+        if self.mode == PathMode::Loop
+            && let Some(&first) = self.waypoints.first()
+        {
+            points.push(first);
+        }
+        points
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn path(mode: PathMode) -> Path {
+        let waypoints = vec![vec2(0.0, 0.0), vec2(1.0, 0.0), vec2(1.0, 1.0)];
+        Path::new(waypoints, mode, 2.0)
+    }
+
+    fn targets(path: &mut Path, count: usize) -> Vec<usize> {
+        (0..count)
+            .map(|_| {
+                path.advance();
+                path.target
+            })
+            .collect()
+    }
+
+    #[test]
+    fn velocity_heads_for_the_target_at_speed() {
+        let mut path = path(PathMode::Loop);
+        path.target = 1;
+        assert_eq!(path.velocity(vec2(0.0, 0.0), 0.1), vec2(2.0, 0.0));
+        assert_eq!(path.target, 1);
+    }
+
+    #[test]
+    fn velocity_lands_on_a_close_waypoint_and_moves_on() {
+        let mut path = path(PathMode::Loop);
+        path.target = 1;
+        let velocity = path.velocity(vec2(0.9, 0.0), 0.1);
+        assert!((velocity - vec2(1.0, 0.0)).length() < 1e-5);
+        assert_eq!(path.target, 2);
+    }
+
+    #[test]
+    fn loop_wraps_to_the_first_waypoint() {
+        assert_eq!(targets(&mut path(PathMode::Loop), 4), [1, 2, 0, 1]);
+    }
+
+    #[test]
+    fn ping_pong_turns_at_both_ends() {
+        assert_eq!(
+            targets(&mut path(PathMode::PingPong), 6),
+            [1, 2, 1, 0, 1, 2]
+        );
+    }
+
+    #[test]
+    fn single_waypoint_stays_put() {
+        let mut path = Path::new(vec![vec2(1.0, 1.0)], PathMode::PingPong, 1.0);
+        assert_eq!(path.velocity(vec2(1.0, 1.0), 0.1), Vec2::ZERO);
+        assert_eq!(path.target, 0);
+        assert_eq!(
+            Path::new(Vec::new(), PathMode::Loop, 1.0).velocity(Vec2::ZERO, 0.1),
+            Vec2::ZERO
+        );
+    }
+
+    #[test]
+    fn snapshots_keep_at_most_max_waypoints() {
+        let snapshot = PathSnapshot {
+            waypoints: vec![[0.0, 0.0]; MAX_WAYPOINTS + 5],
+            mode: PathMode::Loop,
+            speed: 1.0,
+        };
+        assert_eq!(
+            Path::from_snapshot(&snapshot).waypoints.len(),
+            MAX_WAYPOINTS
+        );
+    }
+
+    #[test]
+    fn loop_outline_is_closed() {
+        assert_eq!(path(PathMode::Loop).outline().len(), 4);
+        assert_eq!(path(PathMode::PingPong).outline().len(), 3);
+    }
+}
@@ -0,0 +1,404 @@
+use std::{collections::BTreeMap, fs, num::NonZeroUsize, path::PathBuf, time::Duration};
+
+use let_engine::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    Ctx, TICK_SPEED, assets_dir,
+    error::{Result, SandboxError},
+};
+
+/// Gravity the sandbox starts with. Down is positive y.
+const GRAVITY: [f32; 2] = [0.0, 9.81];
+
+/// The tick wait, gravity and every integration parameter, in a form that can be saved.
+///
+/// `dt` is not stored separately: the physics step always covers one tick wait.
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PhysicsSettings {
+    /// Seconds between ticks, and the physics time step.
+    pub tick_wait: f32,
+    pub gravity: [f32; 2],
+    pub min_ccd_dt: f32,
+    pub contact_damping_ratio: f32,
+    pub contact_natural_frequency: f32,
+    pub joint_natural_frequency: f32,
+    pub joint_damping_ratio: f32,
+    pub warmstart_coefficient: f32,
+    pub length_unit: f32,
+    pub normalized_allowed_linear_error: f32,
+    pub normalized_max_corrective_velocity: f32,
+    pub normalized_prediction_distance: f32,
+    pub num_solver_iterations: usize,
+    pub num_additional_friction_iterations: usize,
+    pub num_internal_pgs_iterations: usize,
+    pub num_internal_stabilization_iterations: usize,
+    pub min_island_size: usize,
+    pub max_ccd_substeps: usize,
+}
+
+impl Default for PhysicsSettings {
+    fn default() -> Self {
+        let defaults = IntegrationParameters::default();
+        Self {
+            tick_wait: TICK_SPEED,
+            gravity: GRAVITY,
+            min_ccd_dt: defaults.min_ccd_dt,
+            contact_damping_ratio: defaults.contact_damping_ratio,
+            contact_natural_frequency: defaults.contact_natural_frequency,
+            joint_natural_frequency: defaults.joint_natural_frequency,
+            joint_damping_ratio: defaults.joint_damping_ratio,
+            warmstart_coefficient: defaults.warmstart_coefficient,
+            length_unit: defaults.length_unit,
+            // Tighter than rapier's defaults, which are tuned for pixel sized units.
+            normalized_allowed_linear_error: 0.0001,
+            normalized_max_corrective_velocity: defaults.normalized_max_corrective_velocity,
+            normalized_prediction_distance: 0.001,
+            num_solver_iterations: defaults.num_solver_iterations.get(),
+            num_additional_friction_iterations: defaults.num_additional_friction_iterations,
+            num_internal_pgs_iterations: defaults.num_internal_pgs_iterations,
+            num_internal_stabilization_iterations: defaults.num_internal_stabilization_iterations,
+            min_island_size: defaults.min_island_size,
+            max_ccd_substeps: defaults.max_ccd_substeps,
+        }
+    }
+}
+
+impl PhysicsSettings {
+    pub fn integration_parameters(&self) -> IntegrationParameters {
+        IntegrationParameters {
+            dt: self.tick_wait,
+            min_ccd_dt: self.min_ccd_dt,
+            contact_damping_ratio: self.contact_damping_ratio,
+            contact_natural_frequency: self.contact_natural_frequency,
+            joint_natural_frequency: self.joint_natural_frequency,
+            joint_damping_ratio: self.joint_damping_ratio,
+            warmstart_coefficient: self.warmstart_coefficient,
+            length_unit: self.length_unit,
+            normalized_allowed_linear_error: self.normalized_allowed_linear_error,
+            normalized_max_corrective_velocity: self.normalized_max_corrective_velocity,
+            normalized_prediction_distance: self.normalized_prediction_distance,
+            num_solver_iterations: NonZeroUsize::new(self.num_solver_iterations)
+                .unwrap_or(NonZeroUsize::MIN),
+            num_additional_friction_iterations: self.num_additional_friction_iterations,
+            num_internal_pgs_iterations: self.num_internal_pgs_iterations,
+            num_internal_stabilization_iterations: self.num_internal_stabilization_iterations,
+            min_island_size: self.min_island_size,
+            max_ccd_substeps: self.max_ccd_substeps,
+        }
+    }
+
+    /// Sets a parameter by its field name, as the console and scripts do.
+    ///
+    /// Counts are rounded to the nearest whole number.
+    pub fn set(&mut self, name: &str, value: f32) -> std::result::Result<(), String> {
+        let count = value.round().max(0.0) as usize;
+        match name {
+            "tick_wait" => self.tick_wait = value.max(0.0001),
+            "min_ccd_dt" => self.min_ccd_dt = value,
+            "contact_damping_ratio" => self.contact_damping_ratio = value,
+            "contact_natural_frequency" => self.contact_natural_frequency = value,
+            "joint_natural_frequency" => self.joint_natural_frequency = value,
+            "joint_damping_ratio" => self.joint_damping_ratio = value,
+            "warmstart_coefficient" => self.warmstart_coefficient = value,
+            "length_unit" => self.length_unit = value,
+            "normalized_allowed_linear_error" => self.normalized_allowed_linear_error = value,
+            "normalized_max_corrective_velocity" => self.normalized_max_corrective_velocity = value,
+            "normalized_prediction_distance" => self.normalized_prediction_distance = value,
+            "num_solver_iterations" => self.num_solver_iterations = count.max(1),
+            "num_additional_friction_iterations" => self.num_additional_friction_iterations = count,
+            "num_internal_pgs_iterations" => self.num_internal_pgs_iterations = count,
+            "num_internal_stabilization_iterations" => {
+                self.num_internal_stabilization_iterations = count
+            }
+            "min_island_size" => self.min_island_size = count,
+            "max_ccd_substeps" => self.max_ccd_substeps = count,
+            other => return Err(format!("unknown physics parameter `{other}`")),
+        }
+        Ok(())
+    }
+
+    pub fn gravity(&self) -> Vec2 {
+        Vec2::from_array(self.gravity)
+    }
+
+    /// Applies the tick wait, integration parameters and gravity to the engine.
+    pub fn apply(&self, ctx: &Ctx) -> Result<()> {
+        ctx.set_tick_settings(
+            TickSettingsBuilder::default()
+                .tick_wait(Duration::from_secs_f32(self.tick_wait))
+                .build()
+                .map_err(SandboxError::scene)?,
+        );
+        ctx.scene
+            .root_layer_mut()
+            .set_physics_parameters(self.integration_parameters());
+        ctx.scene.root_layer_mut().set_gravity(self.gravity());
+        Ok(())
+    }
+
+    /// Built in presets, by name.
+    fn builtin() -> [(&'static str, Self); 4] {
+        let default = Self::default();
+        [
+            ("Default", default.clone()),
+            (
+                "Precise",
+                Self {
+                    tick_wait: 1.0 / 360.0,
+                    num_solver_iterations: 8,
+                    num_internal_pgs_iterations: 2,
+                    max_ccd_substeps: 4,
+                    ..default.clone()
+                },
+            ),
+            (
+                "Fast",
+                Self {
+                    tick_wait: 1.0 / 60.0,
+                    num_solver_iterations: 2,
+                    ..default.clone()
+                },
+            ),
+            (
+                "Zero gravity",
+                Self {
+                    gravity: [0.0, 0.0],
+                    ..default
+                },
+            ),
+        ]
+    }
+}
+
+#[derive(Serialize, Deserialize, Default)]
+struct PresetsFile {
+    presets: BTreeMap<String, PhysicsSettings>,
+}
+
+/// The physics window, editing [`PhysicsSettings`] and managing presets.
+///
+/// Saved presets live in `physics_presets.toml` in the assets directory.
+pub struct PhysicsEditor {
+    pub settings: PhysicsSettings,
+    presets: BTreeMap<String, PhysicsSettings>,
+    preset_name: String,
+    status: String,
+}
+
+impl PhysicsEditor {
+    pub fn new() -> Self {
+        let mut editor = Self {
+            settings: PhysicsSettings::default(),
+            presets: BTreeMap::new(),
+            preset_name: String::new(),
+            status: String::new(),
+        };
+        if let Ok(file) = fs::read_to_string(Self::path()) {
+            match toml::from_str::<PresetsFile>(&file) {
+                Ok(file) => editor.presets = file.presets,
+                Err(e) => {
+                    editor.status = format!("Failed to parse presets: {e}");
+                    log::warn!("{}", editor.status);
+                }
+            }
+        }
+        editor
+    }
+
+    fn path() -> PathBuf {
+        assets_dir().join("physics_presets.toml")
+    }
+
+    fn save_presets(&mut self) {
+        let file = PresetsFile {
+            presets: self.presets.clone(),
+        };
+        self.status = match toml::to_string_pretty(&file)
+            .map_err(|e| e.to_string())
+            .and_then(|file| fs::write(Self::path(), file).map_err(|e| e.to_string()))
+        {
+            Ok(()) => format!("Saved to {}", Self::path().display()),
+            Err(e) => format!("Failed to save presets: {e}"),
+        };
+    }
+
+    /// Shows the editor and applies the settings whenever they change.
+    pub fn ui(&mut self, ui: &mut egui::Ui, ctx: &Ctx) -> Result<()> {
+        let before = self.settings.clone();
+        let settings = &mut self.settings;
+        let mut save = false;
+
+        ui.horizontal(|ui| {
+            egui::ComboBox::from_id_salt("physics_preset")
+                .selected_text("Presets")
+                .show_ui(ui, |ui| {
+                    for (name, preset) in PhysicsSettings::builtin() {
+                        if ui.selectable_label(false, name).clicked() {
+                            *settings = preset;
+                        }
+                    }
+                    for (name, preset) in &self.presets {
+                        if ui.selectable_label(false, name).clicked() {
+                            *settings = preset.clone();
+                        }
+                    }
+                });
+            if ui.button("Reset to defaults").clicked() {
+                *settings = PhysicsSettings::default();
+            }
+        });
+        ui.horizontal(|ui| {
+            ui.add(egui::TextEdit::singleline(&mut self.preset_name).hint_text("Preset name"));
+            let name = self.preset_name.trim().to_string();
+            if ui
+                .add_enabled(!name.is_empty(), egui::Button::new("Save preset"))
+                .clicked()
+            {
+                self.presets.insert(name.clone(), settings.clone());
+                save = true;
+            }
+            if ui
+                .add_enabled(
+                    self.presets.contains_key(&name),
+                    egui::Button::new("Delete preset"),
+                )
+                .clicked()
+            {
+                self.presets.remove(&name);
+                save = true;
+            }
+        });
+        if save {
+            self.save_presets();
+        }
+        let settings = &mut self.settings;
+        if !self.status.is_empty() {
+            ui.label(&self.status);
+        }
+        ui.separator();
+
+        egui::Grid::new("physics").striped(true).show(ui, |ui| {
+            // Edited in ticks per second, which is how it is usually thought of.
+            let mut rate = 1.0 / settings.tick_wait;
+            ui.label("Tick rate (dt)");
+            if ui
+                .add(
+                    egui::DragValue::new(&mut rate)
+                        .range(10.0..=1000.0)
+                        .suffix(" Hz"),
+                )
+                .changed()
+            {
+                settings.tick_wait = 1.0 / rate;
+            }
+            ui.end_row();
+
+            ui.label("Gravity");
+            ui.horizontal(|ui| {
+                ui.add(egui::DragValue::new(&mut settings.gravity[0]).speed(0.1));
+                ui.add(egui::DragValue::new(&mut settings.gravity[1]).speed(0.1));
+            });
+            ui.end_row();
+
+            let floats = [
+                ("Min CCD dt", &mut settings.min_ccd_dt),
+                ("Contact damping ratio", &mut settings.contact_damping_ratio),
+                (
+                    "Contact natural frequency",
+                    &mut settings.contact_natural_frequency,
+                ),
+                (
+                    "Joint natural frequency",
+                    &mut settings.joint_natural_frequency,
+                ),
+                ("Joint damping ratio", &mut settings.joint_damping_ratio),
+                ("Warmstart coefficient", &mut settings.warmstart_coefficient),
+                ("Length unit", &mut settings.length_unit),
+                (
+                    "Allowed linear error",
+                    &mut settings.normalized_allowed_linear_error,
+                ),
+                (
+                    "Max corrective velocity",
+                    &mut settings.normalized_max_corrective_velocity,
+                ),
+                (
+                    "Prediction distance",
+                    &mut settings.normalized_prediction_distance,
+                ),
+            ];
+            for (name, value) in floats {
+                let speed = *value * 0.01 + 0.00001;
+                ui.label(name);
+                ui.add(
+                    egui::DragValue::new(value)
+                        .speed(speed)
+                        .range(0.0..=f32::MAX)
+                        .max_decimals(6),
+                );
+                ui.end_row();
+            }
+
+            let counts = [
+                ("Solver iterations", &mut settings.num_solver_iterations, 1),
+                (
+                    "Additional friction iterations",
+                    &mut settings.num_additional_friction_iterations,
+                    0,
+                ),
+                (
+                    "Internal PGS iterations",
+                    &mut settings.num_internal_pgs_iterations,
+                    0,
+                ),
+                (
+                    "Stabilization iterations",
+                    &mut settings.num_internal_stabilization_iterations,
+                    0,
+                ),
+                ("Min island size", &mut settings.min_island_size, 0),
+                ("Max CCD substeps", &mut settings.max_ccd_substeps, 0),
+            ];
+            for (name, value, min) in counts {
+                ui.label(name);
+                ui.add(egui::DragValue::new(value).range(min..=64));
+                ui.end_row();
+            }
+        });
+
+        if self.settings != before {
+            self.settings.apply(ctx)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn set_rounds_counts_and_clamps() {
+        let mut settings = PhysicsSettings::default();
+        settings.set("num_solver_iterations", 0.2).unwrap();
+        assert_eq!(settings.num_solver_iterations, 1);
+        settings.set("max_ccd_substeps", 2.6).unwrap();
+        assert_eq!(settings.max_ccd_substeps, 3);
+        settings.set("min_island_size", -4.0).unwrap();
+        assert_eq!(settings.min_island_size, 0);
+        settings.set("tick_wait", 0.0).unwrap();
+        assert!(settings.tick_wait > 0.0);
+    }
+
+    #[test]
+    fn set_rejects_unknown_parameters() {
+        let mut settings = PhysicsSettings::default();
+        assert_eq!(
+            settings.set("dt", 0.1),
+            Err("unknown physics parameter `dt`".to_string())
+        );
+        assert!(settings == PhysicsSettings::default());
+    }
+}
@@ -0,0 +1,203 @@
+use std::{
+    collections::VecDeque,
+    time::{Duration, Instant},
+};
+
+use egui::{Color32, Pos2, Sense, Stroke, StrokeKind, vec2};
+
+/// Number of samples kept per series.
+const HISTORY: usize = 600;
+
+/// A rolling window of samples of one metric.
+pub struct Series {
+    name: &'static str,
+    unit: &'static str,
+    color: Color32,
+    values: VecDeque<f32>,
+}
+
+pub struct Stats {
+    pub min: f32,
+    pub avg: f32,
+    pub max: f32,
+    /// Average of the worst 1% of samples.
+    pub low1: f32,
+}
+
+impl Series {
+    fn new(name: &'static str, unit: &'static str, color: Color32) -> Self {
+        Self {
+            name,
+            unit,
+            color,
+            values: VecDeque::with_capacity(HISTORY),
+        }
+    }
+
+    fn push(&mut self, value: f32) {
+        if self.values.len() == HISTORY {
+            self.values.pop_front();
+        }
+        self.values.push_back(value);
+    }
+
+    pub fn stats(&self) -> Option<Stats> {
+        if self.values.is_empty() {
+            return None;
+        }
+        let mut sorted: Vec<f32> = self.values.iter().copied().collect();
+        sorted.sort_by(f32::total_cmp);
+        let worst = (sorted.len() / 100).max(1);
+        let low1 = sorted[sorted.len() - worst..].iter().sum::<f32>() / worst as f32;
+        Some(Stats {
+            min: sorted[0],
+            avg: sorted.iter().sum::<f32>() / sorted.len() as f32,
+            max: sorted[sorted.len() - 1],
+            low1,
+        })
+    }
+
+    fn plot(&self, ui: &mut egui::Ui) {
+        let (response, painter) =
+            ui.allocate_painter(vec2(ui.available_width(), 60.0), Sense::hover());
+        let rect = response.rect;
+        painter.rect_stroke(
+            rect,
+            0.0,
+            Stroke::new(1.0, Color32::DARK_GRAY),
+            StrokeKind::Inside,
+        );
+        let max = self.values.iter().copied().fold(f32::EPSILON, f32::max);
+        let points: Vec<Pos2> = self
+            .values
+            .iter()
+            .enumerate()
+            .map(|(i, value)| {
+                Pos2::new(
+                    rect.left() + i as f32 / (HISTORY - 1) as f32 * rect.width(),
+                    rect.bottom() - value / max * rect.height(),
+                )
+            })
+            .collect();
+        painter.line(points, Stroke::new(1.0, self.color));
+        painter.text(
+            rect.min + vec2(4.0, 2.0),
+            egui::Align2::LEFT_TOP,
+            format!("{} (max {:.2} {})", self.name, max, self.unit),
+            egui::FontId::monospace(11.0),
+            Color32::GRAY,
+        );
+    }
+}
+
+/// Rolling timing statistics for the performance window.
+pub struct Profiler {
+    pub frame_time: Series,
+    pub tick_time: Series,
+    pub label_time: Series,
+    pub objects: Series,
+    pub paused: bool,
+    last_frame: Option<Instant>,
+}
+
+impl Profiler {
+    pub fn new() -> Self {
+        Self {
+            frame_time: Series::new("Frame time", "ms", Color32::LIGHT_GREEN),
+            tick_time: Series::new("Tick time", "ms", Color32::LIGHT_BLUE),
+            label_time: Series::new("Label update", "ms", Color32::YELLOW),
+            objects: Series::new("Objects", "", Color32::LIGHT_GRAY),
+            paused: false,
+            last_frame: None,
+        }
+    }
+
+    /// Records one `Game::update` call.
+    ///
+    /// The physics step runs in the engine and cannot be timed from the game.
+    pub fn record_tick(&mut self, label_time: Duration, tick_time: Duration) {
+        if self.paused {
+            return;
+        }
+        self.tick_time.push(ms(tick_time));
+        self.label_time.push(ms(label_time));
+    }
+
+    /// Records one rendered frame.
+    pub fn record_frame(&mut self, objects: usize) {
+        let now = Instant::now();
+        if self.paused {
+            self.last_frame = None;
+            return;
+        }
+        if let Some(last) = self.last_frame {
+            self.frame_time.push(ms(now - last));
+        }
+        self.last_frame = Some(now);
+        self.objects.push(objects as f32);
+    }
+
+    pub fn ui(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.checkbox(&mut self.paused, "Pause");
+            if ui.button("Clear").clicked() {
+                for series in self.series_mut() {
+                    series.values.clear();
+                }
+            }
+        });
+        egui::Grid::new("profiler_stats")
+            .striped(true)
+            .show(ui, |ui| {
+                for header in ["", "min", "avg", "max", "1% low"] {
+                    ui.strong(header);
+                }
+                ui.end_row();
+                for series in self.series() {
+                    ui.label(series.name);
+                    if let Some(stats) = series.stats() {
+                        for value in [stats.min, stats.avg, stats.max] {
+                            ui.monospace(format!("{value:.2}"));
+                        }
+                        // The worst percent only means something for timings.
+                        if series.unit == "ms" {
+                            ui.monospace(format!("{:.2}", stats.low1));
+                        }
+                    }
+                    ui.end_row();
+                }
+            });
+        if let Some(stats) = self.frame_time.stats() {
+            ui.monospace(format!(
+                "avg {:.1} fps, 1% low {:.1} fps",
+                1000.0 / stats.avg,
+                1000.0 / stats.low1
+            ));
+        }
+        for series in self.series() {
+            series.plot(ui);
+        }
+    }
+
+    fn series(&self) -> [&Series; 4] {
+        [
+            &self.frame_time,
+            &self.tick_time,
+            &self.label_time,
+            &self.objects,
+        ]
+    }
+
+    fn series_mut(&mut self) -> [&mut Series; 4] {
+        [
+            &mut self.frame_time,
+            &mut self.tick_time,
+            &mut self.label_time,
+            &mut self.objects,
+        ]
+    }
+}
+
+fn ms(duration: Duration) -> f32 {
+    duration.as_secs_f32() * 1000.0
+}
@@ -0,0 +1,1191 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+    path::Path,
+    time::{Duration, Instant},
+};
+
+use let_engine::prelude::{
+    gpu::{VulkanTypes, texture::TextureId},
+    *,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    Ctx,
+    conveyors::{Belt, Conveyor, ConveyorStyle},
+    error::{ObjectLookup, Result, SandboxError},
+    fields::{self, Field, FieldKind, FieldStyle},
+    groups::{GROUP_COUNT, GroupTable},
+    materials::{MaterialLibrary, PhysicalMaterial},
+    motors::Motor,
+    paths::{Path, PathSnapshot},
+    soft_body::{SoftBody, SoftBodySettings, SoftStyle},
+};
+
+/// How long the ring of a breaking joint stays visible.
+const SNAP_DURATION: Duration = Duration::from_millis(250);
+
+/// Contact forces below this are not reported, so boxes resting on each other do not
+/// produce force events every tick.
+const IMPACT_FORCE_THRESHOLD: f32 = 1.0;
+
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JointKind {
+    Fixed,
+    /// Rotates freely around the second anchor.
+    Revolute,
+    /// A revolute joint with an angular spring towards the angle it was made at.
+    /// The joined objects do not collide with each other.
+    Hinge {
+        stiffness: f32,
+        damping: f32,
+    },
+    /// Keeps the anchors at most `length` apart.
+    /// The joined objects do not collide with each other.
+    Rope {
+        length: f32,
+    },
+    /// Pulls the anchors towards `rest_length` apart.
+    /// The joined objects do not collide with each other.
+    Spring {
+        rest_length: f32,
+        stiffness: f32,
+        damping: f32,
+    },
+    /// Rotates freely and slides up to `travel` along the first object's y axis,
+    /// sprung towards the anchor. The joined objects do not collide with each other.
+    Wheel {
+        stiffness: f32,
+        damping: f32,
+        travel: f32,
+    },
+}
+
+impl JointKind {
+    /// Whether the joint can be driven by a [`Motor`].
+    pub fn motorized(self) -> bool {
+        matches!(self, JointKind::Revolute | JointKind::Wheel { .. })
+    }
+}
+
+/// Loads above which a joint breaks. `None` never breaks.
+#[derive(Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct BreakLimits {
+    pub force: Option<f32>,
+    pub torque: Option<f32>,
+}
+
+impl BreakLimits {
+    fn exceeded(self, force: f32, torque: f32) -> bool {
+        self.force.is_some_and(|limit| force > limit)
+            || self.torque.is_some_and(|limit| torque > limit)
+    }
+}
+
+/// How a spawned box takes part in the simulation.
+#[derive(Clone, Copy, Default)]
+pub struct BodySettings {
+    pub fixed: bool,
+    /// Detects overlaps instead of colliding.
+    pub sensor: bool,
+    /// Collision group, see [`crate::groups`].
+    pub group: usize,
+    pub material: PhysicalMaterial,
+}
+
+/// The appearances drawn with the sandbox texture, rebuilt when it is reloaded.
+pub struct Textured {
+    pub texture: TextureId,
+    /// The box appearance of every collision group.
+    pub squares: Vec<Appearance<VulkanTypes>>,
+    pub sensor: Appearance<VulkanTypes>,
+    pub sensor_occupied: Appearance<VulkanTypes>,
+    pub circle: Appearance<VulkanTypes>,
+}
+
+/// An image dropped onto single boxes.
+pub struct BoxTexture {
+    pub texture: TextureId,
+    /// The box appearance of every collision group, drawn with the image.
+    pub squares: Vec<Appearance<VulkanTypes>>,
+}
+
+/// Appearances of spawned objects other than the plain box.
+pub struct Style {
+    pub sensor: Appearance<VulkanTypes>,
+    /// A sensor with something inside.
+    pub sensor_occupied: Appearance<VulkanTypes>,
+    pub fields: FieldStyle,
+    /// A unit circle flashed where a joint breaks.
+    pub snap: Appearance<VulkanTypes>,
+    /// A textured disc of radius one.
+    pub circle: Appearance<VulkanTypes>,
+    pub soft: SoftStyle,
+    pub conveyor: ConveyorStyle,
+}
+
+/// A joint added through [`Sandbox::add_joint`].
+pub struct Joint {
+    pub a: ObjectId,
+    pub b: ObjectId,
+    pub kind: JointKind,
+    pub anchor1: Vec2,
+    pub anchor2: Vec2,
+    pub limits: BreakLimits,
+    /// Only used by [`JointKind::motorized`] joints.
+    pub motor: Option<Motor>,
+    handle: ImpulseJointHandle,
+}
+
+/// Scene state shared between the tools and panels.
+pub struct Sandbox {
+    pub spawned_objects: HashSet<ObjectId>,
+    pub selected_object: Option<ObjectId>,
+    pub joints: Vec<Joint>,
+    /// The box appearance, tinted for each collision group.
+    pub squares: Vec<Appearance<VulkanTypes>>,
+    pub groups: GroupTable,
+    pub materials: MaterialLibrary,
+    /// Collision groups of objects not in group 0.
+    group_of: HashMap<ObjectId, usize>,
+    /// Force fields by their zone object. The zones are spawned objects too.
+    pub fields: HashMap<ObjectId, Field>,
+    /// Sensors and the objects currently inside them.
+    pub sensors: HashMap<ObjectId, HashSet<ObjectId>>,
+    /// Soft bodies. Their particles are spawned objects.
+    pub soft_bodies: Vec<SoftBody>,
+    /// Spawned discs, as opposed to boxes.
+    pub circles: HashSet<ObjectId>,
+    /// Kinematic bodies and the paths they follow.
+    pub paths: HashMap<ObjectId, Path>,
+    /// Conveyor belts. They are spawned objects too.
+    pub conveyors: HashMap<ObjectId, Conveyor>,
+    /// Images dropped onto single boxes, by their path.
+    box_textures: HashMap<String, BoxTexture>,
+    /// Boxes drawn with one of `box_textures` instead of `squares`.
+    textured_boxes: HashMap<ObjectId, String>,
+    style: Style,
+    /// Rings of recently broken joints and when they appeared.
+    snaps: Vec<(ObjectId, Instant)>,
+    /// The platform. It is a spawned object, but survives `clear` and `load`.
+    pub ground: ObjectId,
+    /// Stable numbers for spawned objects, used to refer to them in the console.
+    numbers: HashMap<ObjectId, u32>,
+    next_number: u32,
+}
+
+/// A serializable copy of the spawned objects and the joints between them.
+#[derive(Serialize, Deserialize)]
+pub struct Snapshot {
+    pub objects: Vec<ObjectSnapshot>,
+    pub joints: Vec<JointSnapshot>,
+    #[serde(default)]
+    pub groups: GroupTable,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct ObjectSnapshot {
+    pub position: [f32; 2],
+    pub rotation: f32,
+    /// Half extents.
+    pub size: [f32; 2],
+    pub fixed: bool,
+    #[serde(default)]
+    pub sensor: bool,
+    #[serde(default)]
+    pub group: usize,
+    #[serde(default)]
+    pub material: PhysicalMaterial,
+    /// A disc with a radius of `size[0]` instead of a box.
+    #[serde(default)]
+    pub circle: bool,
+    /// Stands for the platform, which is not spawned again on load.
+    #[serde(default)]
+    pub ground: bool,
+    pub linvel: [f32; 2],
+    pub angvel: f32,
+    /// Makes the body kinematic, following the path.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<PathSnapshot>,
+    /// Set for conveyor belts instead of boxes.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub conveyor: Option<Belt>,
+    /// Set for force field zones instead of boxes.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub field: Option<FieldKind>,
+    /// Path of an image dropped onto the box.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub texture: Option<String>,
+}
+
+/// A joint between two objects, referenced by their index in [`Snapshot::objects`].
+#[derive(Serialize, Deserialize)]
+pub struct JointSnapshot {
+    pub a: usize,
+    pub b: usize,
+    pub kind: JointKind,
+    pub anchor1: [f32; 2],
+    pub anchor2: [f32; 2],
+    #[serde(default)]
+    pub limits: BreakLimits,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub motor: Option<Motor>,
+}
+
+impl Snapshot {
+    pub fn read(path: &Path) -> Result<Self> {
+        Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+    }
+
+    pub fn write(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+}
+
+impl Sandbox {
+    /// `squares` holds the box appearance of every collision group.
+    pub fn new(squares: Vec<Appearance<VulkanTypes>>, style: Style, ground: ObjectId) -> Self {
+        let mut sandbox = Self {
+            spawned_objects: HashSet::new(),
+            selected_object: None,
+            joints: Vec::new(),
+            squares,
+            groups: GroupTable::default(),
+            materials: MaterialLibrary::new(),
+            group_of: HashMap::new(),
+            fields: HashMap::new(),
+            sensors: HashMap::new(),
+            soft_bodies: Vec::new(),
+            circles: HashSet::new(),
+            paths: HashMap::new(),
+            conveyors: HashMap::new(),
+            box_textures: HashMap::new(),
+            textured_boxes: HashMap::new(),
+            style,
+            snaps: Vec::new(),
+            ground,
+            numbers: HashMap::new(),
+            next_number: 0,
+        };
+        sandbox.track(ground);
+        sandbox
+    }
+
+    fn track(&mut self, id: ObjectId) {
+        self.spawned_objects.insert(id);
+        self.numbers.insert(id, self.next_number);
+        self.next_number += 1;
+    }
+
+    /// The console number of a spawned object.
+    pub fn number(&self, id: ObjectId) -> Option<u32> {
+        self.numbers.get(&id).copied()
+    }
+
+    /// The number the next spawned object will get.
+    pub fn next_number(&self) -> u32 {
+        self.next_number
+    }
+
+    /// Leaves the next console number unused, for a spawn that was numbered in advance
+    /// but failed.
+    pub fn skip_number(&mut self) {
+        self.next_number += 1;
+    }
+
+    /// The spawned object with the given console number.
+    pub fn by_number(&self, number: u32) -> Option<ObjectId> {
+        self.numbers
+            .iter()
+            .find_map(|(&id, &n)| (n == number).then_some(id))
+    }
+
+    /// Spawned objects drawn with the box appearance.
+    pub fn boxes(&self) -> impl Iterator<Item = ObjectId> + '_ {
+        self.spawned_objects
+            .iter()
+            .copied()
+            .filter(|&id| self.is_box(id))
+    }
+
+    /// Whether the object is drawn with the box appearance, which excludes sensors,
+    /// fields, circles, conveyor belts and soft body particles.
+    pub fn is_box(&self, id: ObjectId) -> bool {
+        !self.fields.contains_key(&id)
+            && !self.sensors.contains_key(&id)
+            && !self.circles.contains(&id)
+            && !self.conveyors.contains_key(&id)
+            && self.soft_body_of(id).is_none()
+    }
+
+    /// The index of the soft body a particle belongs to.
+    pub fn soft_body_of(&self, id: ObjectId) -> Option<usize> {
+        self.soft_bodies
+            .iter()
+            .position(|body| body.particles.contains(&id))
+    }
+
+    /// Spawns a textured box with a collider matching `transform.size`.
+    ///
+    /// Boxes report collision events, which the collision log picks up.
+    pub fn spawn_square(
+        &mut self,
+        ctx: &Ctx,
+        transform: Transform,
+        body: BodySettings,
+    ) -> Result<ObjectId> {
+        self.spawn_body(ctx, transform, body, false)
+    }
+
+    /// Spawns a textured disc like [`Sandbox::spawn_square`], with the radius in
+    /// `transform.size.x`. Discs are not tinted by their collision group.
+    pub fn spawn_circle(
+        &mut self,
+        ctx: &Ctx,
+        mut transform: Transform,
+        body: BodySettings,
+    ) -> Result<ObjectId> {
+        transform.size.y = transform.size.x;
+        let id = self.spawn_body(ctx, transform, body, true)?;
+        self.circles.insert(id);
+        Ok(id)
+    }
+
+    fn spawn_body(
+        &mut self,
+        ctx: &Ctx,
+        transform: Transform,
+        body: BodySettings,
+        circle: bool,
+    ) -> Result<ObjectId> {
+        let group = body.group.min(GROUP_COUNT - 1);
+        let (appearance, shape) = match (body.sensor, circle) {
+            (true, _) => (
+                &self.style.sensor,
+                ColliderBuilder::square(transform.size.x, transform.size.y),
+            ),
+            (false, true) => (
+                &self.style.circle,
+                ColliderBuilder::circle(transform.size.x),
+            ),
+            (false, false) => (
+                &self.squares[group],
+                ColliderBuilder::square(transform.size.x, transform.size.y),
+            ),
+        };
+        let mut object = ObjectBuilder::new(appearance.clone());
+        object.set_collider(Some(
+            body.material
+                .build(shape)
+                .sensor(body.sensor)
+                .collision_groups(self.groups.interaction_groups(group))
+                .active_events(ActiveEvents::COLLISION_EVENTS | ActiveEvents::CONTACT_FORCE_EVENTS)
+                .contact_force_event_threshold(IMPACT_FORCE_THRESHOLD)
+                .build(),
+        ));
+        let rigid_body_type = if body.fixed {
+            RigidBodyType::Fixed
+        } else {
+            RigidBodyType::Dynamic
+        };
+        object.set_rigid_body(Some(RigidBodyBuilder::new(rigid_body_type).build()));
+        object.transform = size_appearance(&mut object.appearance, transform);
+        let id = ctx
+            .scene
+            .add_object(ctx.scene.root_layer_id(), object)
+            .map_err(SandboxError::scene)?;
+        if body.sensor {
+            self.sensors.insert(id, HashSet::new());
+        }
+        if group != 0 {
+            self.group_of.insert(id, group);
+        }
+        self.track(id);
+        Ok(id)
+    }
+
+    /// The collision group of a spawned object.
+    pub fn group(&self, id: ObjectId) -> usize {
+        self.group_of.get(&id).copied().unwrap_or_default()
+    }
+
+    /// Moves an object to another collision group, tinting it if it is a box.
+    pub fn set_group(&mut self, ctx: &Ctx, id: ObjectId, group: usize) -> Result<()> {
+        let group = group.min(GROUP_COUNT - 1);
+        if group == 0 {
+            self.group_of.remove(&id);
+        } else {
+            self.group_of.insert(id, group);
+        }
+        self.apply_group(ctx, id)?;
+        if self.is_box(id) {
+            Self::restyle(ctx, id, self.box_appearance(id))?;
+        }
+        Ok(())
+    }
+
+    /// The appearance of a box, either its dropped image or the sandbox texture, tinted
+    /// in its group's color.
+    fn box_appearance(&self, id: ObjectId) -> &Appearance<VulkanTypes> {
+        let squares = self
+            .textured_boxes
+            .get(&id)
+            .and_then(|path| self.box_textures.get(path))
+            .map_or(&self.squares, |texture| &texture.squares);
+        &squares[self.group(id)]
+    }
+
+    /// Whether an image was loaded for boxes under this path.
+    pub fn has_box_texture(&self, path: &str) -> bool {
+        self.box_textures.contains_key(path)
+    }
+
+    /// Paths of the images loaded for boxes.
+    #[cfg(debug_assertions)]
+    pub fn box_texture_paths(&self) -> impl Iterator<Item = &str> {
+        self.box_textures.keys().map(String::as_str)
+    }
+
+    /// Adds an image for boxes, replacing and freeing an older one under the same path.
+    /// Boxes already drawn with that path are restyled.
+    pub fn add_box_texture(&mut self, ctx: &Ctx, path: String, texture: BoxTexture) -> Result<()> {
+        if let Some(old) = self.box_textures.insert(path.clone(), texture) {
+            ctx.gpu
+                .remove_texture(old.texture)
+                .map_err(SandboxError::gpu)?;
+        }
+        for (&id, _) in self
+            .textured_boxes
+            .iter()
+            .filter(|(_, other)| **other == path)
+        {
+            Self::restyle(ctx, id, self.box_appearance(id))?;
+        }
+        Ok(())
+    }
+
+    /// Draws a box with an image added through [`Sandbox::add_box_texture`], or with the
+    /// sandbox texture again for `None`. Other objects are left alone.
+    pub fn texture_box(&mut self, ctx: &Ctx, id: ObjectId, path: Option<String>) -> Result<()> {
+        if !self.is_box(id) {
+            return Ok(());
+        }
+        match path {
+            Some(path) if self.box_textures.contains_key(&path) => {
+                self.textured_boxes.insert(id, path);
+            }
+            _ => {
+                self.textured_boxes.remove(&id);
+            }
+        }
+        Self::restyle(ctx, id, self.box_appearance(id))
+    }
+
+    /// Swaps every textured appearance after the texture changed, restyling all objects
+    /// drawn with it and freeing the old texture. Boxes keep images dropped onto them.
+    pub fn set_texture(&mut self, ctx: &Ctx, textured: Textured) -> Result<()> {
+        let old = self.style.soft.texture;
+        self.squares = textured.squares;
+        self.style.sensor = textured.sensor;
+        self.style.sensor_occupied = textured.sensor_occupied;
+        self.style.circle = textured.circle;
+        self.style.soft.texture = textured.texture;
+        self.style.conveyor.texture = textured.texture;
+
+        for id in self.boxes() {
+            Self::restyle(ctx, id, self.box_appearance(id))?;
+        }
+        for (&id, occupants) in &self.sensors {
+            let appearance = if occupants.is_empty() {
+                &self.style.sensor
+            } else {
+                &self.style.sensor_occupied
+            };
+            Self::restyle(ctx, id, appearance)?;
+        }
+        for &id in &self.circles {
+            // Sensor discs are drawn as sensors.
+            if !self.sensors.contains_key(&id) {
+                Self::restyle(ctx, id, &self.style.circle)?;
+            }
+        }
+        for body in &self.soft_bodies {
+            let appearance = self.style.soft.appearance(ctx, body.model())?;
+            Self::restyle(ctx, body.mesh, &appearance)?;
+        }
+        for (&id, conveyor) in &self.conveyors {
+            let appearance = self.style.conveyor.appearance(ctx, conveyor.model())?;
+            Self::restyle(ctx, id, &appearance)?;
+        }
+        if old != textured.texture {
+            ctx.gpu.remove_texture(old).map_err(SandboxError::gpu)?;
+        }
+        Ok(())
+    }
+
+    fn apply_group(&self, ctx: &Ctx, id: ObjectId) -> Result<()> {
+        let groups = self.groups.interaction_groups(self.group(id));
+        if let Some(collider) = ctx.scene.object_mut(id).or_stale(id)?.collider_mut() {
+            collider.set_collision_groups(groups);
+        }
+        Ok(())
+    }
+
+    /// Updates every collider after the filter matrix changed.
+    pub fn apply_groups(&self, ctx: &Ctx) -> Result<()> {
+        for &id in &self.spawned_objects {
+            self.apply_group(ctx, id)?;
+        }
+        Ok(())
+    }
+
+    /// Swaps an object's appearance, keeping its size.
+    pub fn restyle(ctx: &Ctx, id: ObjectId, appearance: &Appearance<VulkanTypes>) -> Result<()> {
+        let object = ctx.scene.object_mut(id).or_stale(id)?;
+        let transform = *object.appearance.transform();
+        object.appearance = appearance.clone();
+        object.appearance.set_transform(transform);
+        Ok(())
+    }
+
+    /// Records that `other` entered or left a sensor, highlighting it while occupied.
+    pub fn set_occupant(
+        &mut self,
+        ctx: &Ctx,
+        sensor: ObjectId,
+        other: ObjectId,
+        inside: bool,
+    ) -> Result<()> {
+        let Some(occupants) = self.sensors.get_mut(&sensor) else {
+            return Ok(());
+        };
+        let was_empty = occupants.is_empty();
+        if inside {
+            occupants.insert(other);
+        } else {
+            occupants.remove(&other);
+        }
+        match (was_empty, occupants.is_empty()) {
+            (true, false) => Self::restyle(ctx, sensor, &self.style.sensor_occupied),
+            (false, true) => Self::restyle(ctx, sensor, &self.style.sensor),
+            _ => Ok(()),
+        }
+    }
+
+    /// Records that `other` started or stopped touching a belt.
+    pub fn set_belt_contact(&mut self, belt: ObjectId, other: ObjectId, touching: bool) {
+        if let Some(conveyor) = self.conveyors.get_mut(&belt) {
+            conveyor.set_contact(other, touching);
+        }
+    }
+
+    /// Spawns a force field zone. `transform.size` holds the half extents, or the radius
+    /// in `x` for radial fields.
+    pub fn spawn_field(
+        &mut self,
+        ctx: &Ctx,
+        kind: FieldKind,
+        mut transform: Transform,
+    ) -> Result<ObjectId> {
+        if kind.radial() {
+            transform.size.y = transform.size.x;
+        }
+        let mut object = ObjectBuilder::new(self.style.fields.zone(kind).clone());
+        object.set_collider(Some(fields::collider(
+            kind,
+            transform.size,
+            self.groups.interaction_groups(0),
+        )));
+        object.set_rigid_body(Some(RigidBodyBuilder::fixed().build()));
+        object.transform = size_appearance(&mut object.appearance, transform);
+        let id = ctx
+            .scene
+            .add_object(ctx.scene.root_layer_id(), object)
+            .map_err(SandboxError::scene)?;
+        // Placed by `fields::apply` on the next tick.
+        let arrow = ctx
+            .scene
+            .add_object(
+                ctx.scene.root_layer_id(),
+                ObjectBuilder::new(self.style.fields.arrow.clone()),
+            )
+            .map_err(SandboxError::scene)?;
+        self.fields.insert(id, Field { kind, arrow });
+        self.track(id);
+        Ok(id)
+    }
+
+    /// Spawns a fixed conveyor belt with the half extents in `transform.size`. The belt
+    /// runs along its x axis.
+    pub fn spawn_conveyor(
+        &mut self,
+        ctx: &Ctx,
+        transform: Transform,
+        belt: Belt,
+    ) -> Result<ObjectId> {
+        let (model, appearance) = self.style.conveyor.belt(ctx, transform.size)?;
+        let mut object = ObjectBuilder::new(appearance);
+        object.set_collider(Some(
+            ColliderBuilder::square(transform.size.x, transform.size.y)
+                .collision_groups(self.groups.interaction_groups(0))
+                .active_events(ActiveEvents::COLLISION_EVENTS)
+                .build(),
+        ));
+        object.set_rigid_body(Some(RigidBodyBuilder::fixed().build()));
+        object.transform = size_appearance(&mut object.appearance, transform);
+        let id = ctx
+            .scene
+            .add_object(ctx.scene.root_layer_id(), object)
+            .map_err(SandboxError::scene)?;
+        self.conveyors
+            .insert(id, Conveyor::new(belt, transform.size, model));
+        self.track(id);
+        Ok(id)
+    }
+
+    /// Spawns a soft body around `center`.
+    pub fn spawn_soft_body(
+        &mut self,
+        ctx: &Ctx,
+        center: Vec2,
+        settings: SoftBodySettings,
+    ) -> Result<()> {
+        let mut lattice = settings.lattice();
+        for point in &mut lattice.points {
+            *point += center;
+        }
+        let mut particles = Vec::with_capacity(lattice.points.len());
+        for &point in &lattice.points {
+            let mut particle = ObjectBuilder::new(self.squares[0].clone());
+            particle.appearance.set_visible(false);
+            particle.set_collider(Some(
+                ColliderBuilder::circle(lattice.particle_radius)
+                    .collision_groups(self.groups.interaction_groups(0))
+                    .build(),
+            ));
+            particle.set_rigid_body(Some(RigidBodyBuilder::dynamic().build()));
+            particle.transform.position = point;
+            let id = ctx
+                .scene
+                .add_object(ctx.scene.root_layer_id(), particle)
+                .map_err(SandboxError::scene)?;
+            self.track(id);
+            particles.push(id);
+        }
+        for &(a, b) in &lattice.springs {
+            let kind = JointKind::Spring {
+                rest_length: lattice.points[a].distance(lattice.points[b]),
+                stiffness: settings.stiffness,
+                damping: settings.damping,
+            };
+            self.add_joint(
+                ctx,
+                (particles[a], particles[b]),
+                kind,
+                (Vec2::ZERO, Vec2::ZERO),
+                BreakLimits::default(),
+            )?;
+        }
+        let (model, appearance) = self.style.soft.mesh(ctx, &lattice)?;
+        let mesh = ctx
+            .scene
+            .add_object(ctx.scene.root_layer_id(), ObjectBuilder::new(appearance))
+            .map_err(SandboxError::scene)?;
+        self.soft_bodies
+            .push(SoftBody::new(particles, lattice, mesh, model));
+        Ok(())
+    }
+
+    /// Removes a spawned object, returning whether it was one.
+    ///
+    /// The ground is kept, since scenes are loaded onto it.
+    pub fn remove_object(&mut self, ctx: &Ctx, id: ObjectId) -> bool {
+        if id == self.ground || !self.spawned_objects.remove(&id) {
+            return false;
+        }
+        self.numbers.remove(&id);
+        self.group_of.remove(&id);
+        if self.selected_object == Some(id) {
+            self.selected_object = None;
+        }
+        // The engine removes attached joints along with the object.
+        self.joints.retain(|joint| joint.a != id && joint.b != id);
+        if let Some(field) = self.fields.remove(&id) {
+            ctx.scene.remove_object(field.arrow);
+        }
+        self.sensors.remove(&id);
+        self.circles.remove(&id);
+        self.paths.remove(&id);
+        if let Some(conveyor) = self.conveyors.remove(&id)
+            && let Err(e) = ctx.gpu.remove_model(conveyor.model())
+        {
+            log::warn!("Failed to unload a belt model: {e:?}");
+        }
+        self.textured_boxes.remove(&id);
+        if let Some(index) = self.soft_body_of(id) {
+            let body = self.soft_bodies.remove(index);
+            ctx.scene.remove_object(body.mesh);
+            if let Err(e) = ctx.gpu.remove_model(body.model()) {
+                log::warn!("Failed to unload a soft body mesh: {e:?}");
+            }
+            for particle in body.particles {
+                if particle != id {
+                    self.remove_object(ctx, particle);
+                }
+            }
+        }
+        // Removed objects do not get an intersection stopped event.
+        let sensors: Vec<ObjectId> = self
+            .sensors
+            .iter()
+            .filter(|(_, occupants)| occupants.contains(&id))
+            .map(|(&sensor, _)| sensor)
+            .collect();
+        for sensor in sensors {
+            if let Err(e) = self.set_occupant(ctx, sensor, id, false) {
+                log::warn!("Failed to update sensor: {e}");
+            }
+        }
+        for conveyor in self.conveyors.values_mut() {
+            conveyor.set_contact(id, false);
+        }
+        ctx.scene.remove_object(id);
+        true
+    }
+
+    /// Removes every spawned object except the ground.
+    pub fn clear(&mut self, ctx: &Ctx) {
+        let ids: Vec<ObjectId> = self.spawned_objects.iter().copied().collect();
+        for id in ids {
+            if id != self.ground {
+                self.remove_object(ctx, id);
+            }
+        }
+    }
+
+    /// Joins two objects. The anchors are relative to the respective object.
+    pub fn add_joint(
+        &mut self,
+        ctx: &Ctx,
+        (a, b): (ObjectId, ObjectId),
+        kind: JointKind,
+        (anchor1, anchor2): (Vec2, Vec2),
+        limits: BreakLimits,
+    ) -> Result<()> {
+        let added = match kind {
+            JointKind::Fixed => ctx.scene.add_joint(
+                a,
+                b,
+                FixedJointBuilder::new()
+                    .local_anchor1(anchor1)
+                    .local_anchor2(anchor2),
+                true,
+            ),
+            JointKind::Revolute => ctx.scene.add_joint(
+                a,
+                b,
+                RevoluteJointBuilder::new()
+                    .local_anchor1(anchor1)
+                    .local_anchor2(anchor2),
+                true,
+            ),
+            JointKind::Hinge { stiffness, damping } => {
+                let rotation = |id| -> Result<f32> {
+                    Ok(ctx.scene.object(id).or_stale(id)?.transform.rotation)
+                };
+                let angle = rotation(b)? - rotation(a)?;
+                ctx.scene.add_joint(
+                    a,
+                    b,
+                    RevoluteJointBuilder::new()
+                        .local_anchor1(anchor1)
+                        .local_anchor2(anchor2)
+                        .motor_position(angle, stiffness, damping)
+                        .contacts_enabled(false),
+                    true,
+                )
+            }
+            JointKind::Rope { length } => ctx.scene.add_joint(
+                a,
+                b,
+                RopeJointBuilder::new(length)
+                    .local_anchor1(anchor1)
+                    .local_anchor2(anchor2)
+                    .contacts_enabled(false),
+                true,
+            ),
+            JointKind::Spring {
+                rest_length,
+                stiffness,
+                damping,
+            } => ctx.scene.add_joint(
+                a,
+                b,
+                SpringJointBuilder::new(rest_length, stiffness, damping)
+                    .local_anchor1(anchor1)
+                    .local_anchor2(anchor2)
+                    .contacts_enabled(false),
+                true,
+            ),
+            JointKind::Wheel {
+                stiffness,
+                damping,
+                travel,
+            } => ctx.scene.add_joint(
+                a,
+                b,
+                GenericJointBuilder::new(JointAxesMask::LIN_X)
+                    .local_anchor1(anchor1)
+                    .local_anchor2(anchor2)
+                    .limits(JointAxis::LinY, [-travel, travel])
+                    .motor_position(JointAxis::LinY, 0.0, stiffness, damping)
+                    .contacts_enabled(false),
+                true,
+            ),
+        };
+        let handle = added.map_err(SandboxError::scene)?;
+        self.joints.push(Joint {
+            a,
+            b,
+            kind,
+            anchor1,
+            anchor2,
+            limits,
+            motor: None,
+            handle,
+        });
+        Ok(())
+    }
+
+    /// Sets the joint motors from the held keys. Motors without a held key spin freely.
+    pub fn drive_motors(&self, ctx: &Ctx, held_keys: &HashSet<String>) -> Result<()> {
+        for joint in &self.joints {
+            if !joint.kind.motorized() {
+                continue;
+            }
+            let direction = joint
+                .motor
+                .as_ref()
+                .map_or(0.0, |motor| motor.direction(held_keys));
+            let (velocity, max_torque) = match &joint.motor {
+                Some(motor) if direction != 0.0 => (motor.speed * direction, motor.max_torque),
+                _ => (0.0, 0.0),
+            };
+            let Some(data) = ctx.scene.joint_mut(joint.handle) else {
+                continue;
+            };
+            data.set_motor_velocity(JointAxis::AngX, velocity, 1.0);
+            data.set_motor_max_force(JointAxis::AngX, max_torque);
+            if max_torque > 0.0 {
+                for id in [joint.a, joint.b] {
+                    if let Some(body) = ctx.scene.object_mut(id).or_stale(id)?.rigid_body_mut() {
+                        body.wake_up(true);
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Makes an object kinematic and sends it along the path, replacing any previous one.
+    pub fn set_path(&mut self, ctx: &Ctx, id: ObjectId, mut path: Path) -> Result<()> {
+        let object = ctx.scene.object_mut(id).or_stale(id)?;
+        let Some(body) = object.rigid_body_mut() else {
+            return Ok(());
+        };
+        path.fixed = match self.paths.get(&id) {
+            Some(previous) => previous.fixed,
+            None => body.is_fixed(),
+        };
+        body.set_body_type(RigidBodyType::KinematicVelocityBased, true);
+        self.paths.insert(id, path);
+        Ok(())
+    }
+
+    /// Stops an object following its path, making it fixed or dynamic again.
+    pub fn remove_path(&mut self, ctx: &Ctx, id: ObjectId) -> Result<()> {
+        let Some(path) = self.paths.remove(&id) else {
+            return Ok(());
+        };
+        if let Some(body) = ctx.scene.object_mut(id).or_stale(id)?.rigid_body_mut() {
+            let body_type = if path.fixed {
+                RigidBodyType::Fixed
+            } else {
+                RigidBodyType::Dynamic
+            };
+            body.set_body_type(body_type, true);
+            body.set_linvel(Vec2::ZERO.into(), true);
+            body.set_angvel(0.0, true);
+        }
+        Ok(())
+    }
+
+    /// Sets the velocity of every kinematic body towards its next waypoint.
+    pub fn follow_paths(&mut self, ctx: &Ctx, dt: f32) -> Result<()> {
+        for (&id, path) in &mut self.paths {
+            let object = ctx.scene.object_mut(id).or_stale(id)?;
+            let velocity = path.velocity(object.transform.position, dt);
+            if let Some(body) = object.rigid_body_mut() {
+                body.set_linvel(velocity.into(), true);
+                body.set_angvel(0.0, true);
+            }
+        }
+        Ok(())
+    }
+
+    /// Removes the joints whose last impulse exceeded their limits, flashing a ring
+    /// where they were.
+    pub fn break_joints(&mut self, ctx: &Ctx, dt: f32) -> Result<()> {
+        self.snaps.retain(|&(id, start)| {
+            let progress = start.elapsed().as_secs_f32() / SNAP_DURATION.as_secs_f32();
+            if progress >= 1.0 {
+                ctx.scene.remove_object(id);
+                return false;
+            }
+            if let Ok(ring) = ctx.scene.object_mut(id).or_stale(id) {
+                ring.appearance.transform_mut().size = Vec2::splat(0.02 + progress * 0.1);
+            }
+            true
+        });
+
+        let mut index = 0;
+        while index < self.joints.len() {
+            let joint = &self.joints[index];
+            if joint.limits == BreakLimits::default() {
+                index += 1;
+                continue;
+            }
+            let Some(impulses) = ctx.scene.joint(joint.handle).map(|joint| joint.impulses) else {
+                index += 1;
+                continue;
+            };
+            // The impulses the solver applied during the last step.
+            let force = vec2(impulses.x, impulses.y).length() / dt;
+            let torque = impulses.z.abs() / dt;
+            if !joint.limits.exceeded(force, torque) {
+                index += 1;
+                continue;
+            }
+            let joint = self.joints.remove(index);
+            ctx.scene.remove_joint(joint.handle);
+            let object = ctx.scene.object(joint.a).or_stale(joint.a)?;
+            let position = object.transform.position
+                + Vec2::from_angle(object.transform.rotation).rotate(joint.anchor1);
+            log::info!(
+                "Joint between #{} and #{} broke at {force:.2} N, {torque:.3} N·m",
+                self.number(joint.a).unwrap_or_default(),
+                self.number(joint.b).unwrap_or_default(),
+            );
+            let mut ring = ObjectBuilder::new(self.style.snap.clone());
+            ring.transform.position = position;
+            ring.appearance.transform_mut().size = Vec2::splat(0.02);
+            let ring = ctx
+                .scene
+                .add_object(ctx.scene.root_layer_id(), ring)
+                .map_err(SandboxError::scene)?;
+            self.snaps.push((ring, Instant::now()));
+        }
+        Ok(())
+    }
+
+    /// Captures the current state of every spawned object and joint, except soft bodies.
+    pub fn snapshot(&self, ctx: &Ctx) -> Result<Snapshot> {
+        let ids: Vec<ObjectId> = self
+            .spawned_objects
+            .iter()
+            .copied()
+            .filter(|&id| self.soft_body_of(id).is_none())
+            .collect();
+        let mut objects = Vec::with_capacity(ids.len());
+        for &id in &ids {
+            let object = ctx.scene.object(id).or_stale(id)?;
+            let size = object.transform.size * object.appearance.transform().size;
+            let path = self.paths.get(&id);
+            let (fixed, linvel, angvel) = match object.rigid_body() {
+                Some(body) => (
+                    path.map_or(body.is_fixed(), |path| path.fixed),
+                    [body.linvel().x, body.linvel().y],
+                    body.angvel(),
+                ),
+                None => (true, [0.0; 2], 0.0),
+            };
+            objects.push(ObjectSnapshot {
+                position: object.transform.position.to_array(),
+                rotation: object.transform.rotation,
+                size: size.to_array(),
+                fixed,
+                sensor: self.sensors.contains_key(&id),
+                group: self.group(id),
+                circle: self.circles.contains(&id),
+                material: object
+                    .collider()
+                    .map(PhysicalMaterial::of)
+                    .unwrap_or_default(),
+                ground: id == self.ground,
+                linvel,
+                angvel,
+                path: path.map(Path::snapshot),
+                conveyor: self.conveyors.get(&id).map(|conveyor| conveyor.belt),
+                field: self.fields.get(&id).map(|field| field.kind),
+                texture: self.textured_boxes.get(&id).cloned(),
+            });
+        }
+        let index = |id| ids.iter().position(|&other| other == id);
+        let joints = self
+            .joints
+            .iter()
+            .filter_map(|joint| {
+                Some(JointSnapshot {
+                    a: index(joint.a)?,
+                    b: index(joint.b)?,
+                    kind: joint.kind,
+                    anchor1: joint.anchor1.to_array(),
+                    anchor2: joint.anchor2.to_array(),
+                    limits: joint.limits,
+                    motor: joint.motor.clone(),
+                })
+            })
+            .collect();
+        Ok(Snapshot {
+            objects,
+            joints,
+            groups: self.groups.clone(),
+        })
+    }
+
+    /// Replaces the spawned objects and joints with the ones in the snapshot.
+    ///
+    /// Box images are only used if they were added beforehand, the other boxes get the
+    /// sandbox texture.
+    pub fn load(&mut self, ctx: &Ctx, snapshot: &Snapshot) -> Result<()> {
+        self.clear(ctx);
+        self.groups = snapshot.groups.clone();
+        self.groups.normalize();
+        self.apply_groups(ctx)?;
+        let mut ids = Vec::with_capacity(snapshot.objects.len());
+        for object in &snapshot.objects {
+            if object.ground {
+                self.set_group(ctx, self.ground, object.group)?;
+                self.remove_path(ctx, self.ground)?;
+                if let Some(path) = &object.path {
+                    self.set_path(ctx, self.ground, Path::from_snapshot(path))?;
+                }
+                ids.push(self.ground);
+                continue;
+            }
+            let transform: Transform = (
+                Vec2::from_array(object.position),
+                Vec2::from_array(object.size),
+                object.rotation,
+            )
+                .into();
+            if let Some(kind) = object.field {
+                ids.push(self.spawn_field(ctx, kind, transform)?);
+                continue;
+            }
+            let body = BodySettings {
+                fixed: object.fixed,
+                sensor: object.sensor,
+                group: object.group,
+                material: object.material,
+            };
+            let id = if let Some(belt) = object.conveyor {
+                let id = self.spawn_conveyor(ctx, transform, belt)?;
+                self.set_group(ctx, id, object.group)?;
+                id
+            } else if object.circle {
+                self.spawn_circle(ctx, transform, body)?
+            } else {
+                self.spawn_square(ctx, transform, body)?
+            };
+            if let Some(body) = ctx.scene.object_mut(id).or_stale(id)?.rigid_body_mut() {
+                body.set_linvel(Vec2::from_array(object.linvel).into(), true);
+                body.set_angvel(object.angvel, true);
+            }
+            if let Some(path) = &object.path {
+                self.set_path(ctx, id, Path::from_snapshot(path))?;
+            }
+            if object.texture.is_some() {
+                self.texture_box(ctx, id, object.texture.clone())?;
+            }
+            ids.push(id);
+        }
+        for joint in &snapshot.joints {
+            let (Some(&a), Some(&b)) = (ids.get(joint.a), ids.get(joint.b)) else {
+                log::warn!("Skipping a joint with an invalid object index");
+                continue;
+            };
+            self.add_joint(
+                ctx,
+                (a, b),
+                joint.kind,
+                (
+                    Vec2::from_array(joint.anchor1),
+                    Vec2::from_array(joint.anchor2),
+                ),
+                joint.limits,
+            )?;
+            if let Some(added) = self.joints.last_mut() {
+                added.motor = joint.motor.clone();
+            }
+        }
+        Ok(())
+    }
+
+    /// The spawned object under the given world position.
+    pub fn object_at(&self, ctx: &Ctx, position: Vec2) -> Option<ObjectId> {
+        ctx.scene
+            .root_layer()
+            .cast_ray(position, vec2(0.0, 0.0), 0.0, true)
+            .filter(|id| self.spawned_objects.contains(id))
+    }
+
+    /// All spawned objects touching a circle.
+    pub fn objects_in_circle(&self, ctx: &Ctx, center: Vec2, radius: f32) -> Vec<ObjectId> {
+        self.objects_in_shape(ctx, Shape::circle(radius), (center, 0.0))
+    }
+
+    /// All spawned objects touching a shape at the given position and rotation.
+    pub fn objects_in_shape(
+        &self,
+        ctx: &Ctx,
+        shape: Shape,
+        placement: (Vec2, f32),
+    ) -> Vec<ObjectId> {
+        ctx.scene
+            .root_layer()
+            .intersections_with_shape(shape, placement)
+            .into_iter()
+            .filter(|id| self.spawned_objects.contains(id))
+            .collect()
+    }
+
+    /// All spawned objects under the given world position.
+    pub fn objects_at(&self, ctx: &Ctx, position: Vec2) -> Vec<ObjectId> {
+        ctx.scene
+            .root_layer()
+            .intersections_with_ray(position, vec2(0.0, 0.0), 0.0, true)
+            .into_iter()
+            .filter(|id| self.spawned_objects.contains(id))
+            .collect()
+    }
+}
+
+/// Moves the size of `transform` onto the appearance and returns the transform for the
+/// object, so colliders built for that size are not scaled a second time.
+fn size_appearance(
+    appearance: &mut Appearance<VulkanTypes>,
+    mut transform: Transform,
+) -> Transform {
+    let mut appearance_transform = *appearance.transform();
+    appearance_transform.size = transform.size;
+    appearance.set_transform(appearance_transform);
+    transform.size = vec2(1.0, 1.0);
+    transform
+}
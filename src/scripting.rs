@@ -0,0 +1,445 @@
+use std::{
+    cell::RefCell,
+    collections::{HashMap, HashSet},
+    fs,
+    path::PathBuf,
+    rc::Rc,
+    time::{Duration, Instant, SystemTime},
+};
+
+use let_engine::prelude::*;
+use rhai::{AST, Array, CallFnOptions, Dynamic, Engine, EvalAltResult, FLOAT, Map, Scope};
+
+use crate::{
+    assets_dir,
+    commands::Command,
+    error::{Result, SandboxError},
+    sandbox::{BodySettings, JointKind},
+};
+
+/// How often the script file is checked for changes.
+const RELOAD_INTERVAL: Duration = Duration::from_millis(500);
+
+const API: &str = "\
+setup()                              called once after loading
+tick(dt)                             called every tick
+spawn_box(x, y, width, height[, fixed]) -> number
+                                     the box exists from the next tick on
+joint(\"fixed\" | \"revolute\", a, b)
+remove(number)
+set_velocity(number, x, y)
+position(number) -> [x, y]           [] if the object is gone
+velocity(number) -> [x, y]
+set_gravity(x, y)
+set_timescale(scale)
+set_physics(name, value)             names as in physics_presets.toml,
+                                     like \"num_solver_iterations\"
+clear()
+cursor() -> [x, y]
+primary_down(), secondary_down()
+key_down(name)                       key names as in bindings.toml
+this                                 a map kept between calls";
+
+/// What the scripts see of the sandbox during one call.
+#[derive(Default)]
+pub struct ScriptFrame {
+    pub cursor: Vec2,
+    pub primary: bool,
+    pub secondary: bool,
+    pub keys: HashSet<String>,
+    /// Position and velocity of every spawned object by number.
+    pub objects: HashMap<u32, (Vec2, Vec2)>,
+    /// The number the next spawned object will get.
+    pub next_number: u32,
+}
+
+/// State shared with the functions registered in the script engine.
+///
+/// Scripts cannot touch the scene directly, so their calls queue console commands
+/// that the game runs afterwards. Spawned objects get their numbers in order, which
+/// lets `spawn_box` return the number right away. A spawn that fails leaves its number
+/// unused, so the other numbers stay right.
+#[derive(Default)]
+struct Shared {
+    frame: ScriptFrame,
+    commands: Vec<Command>,
+    /// Whether the script asked to clear the scene.
+    clear: bool,
+}
+
+/// Commands queued by one script call.
+pub struct ScriptRun {
+    /// Clear the scene before running the commands.
+    pub clear: bool,
+    pub commands: Vec<Command>,
+}
+
+/// Runs a Rhai script defining `setup()` and `tick(dt)`, reloading it when it changes.
+pub struct Scripting {
+    engine: Engine,
+    shared: Rc<RefCell<Shared>>,
+    path: String,
+    ast: Option<AST>,
+    /// `this` inside the script functions.
+    state: Dynamic,
+    modified: Option<SystemTime>,
+    last_check: Instant,
+    /// Run `setup()` on the next update.
+    needs_setup: bool,
+    /// Clear the scene when the script is reloaded.
+    reset_on_reload: bool,
+    status: String,
+}
+
+impl Scripting {
+    pub fn new() -> Self {
+        let shared = Rc::new(RefCell::new(Shared::default()));
+        let mut engine = Engine::new();
+        engine.set_max_operations(1_000_000);
+        engine.on_print(|text| log::info!("[script] {text}"));
+        engine.on_debug(|text, _, position| log::debug!("[script {position}] {text}"));
+        register_api(&mut engine, &shared);
+        Self {
+            engine,
+            shared,
+            path: assets_dir()
+                .join("scripts/scenario.rhai")
+                .to_string_lossy()
+                .into_owned(),
+            ast: None,
+            state: Dynamic::from_map(Map::new()),
+            modified: None,
+            last_check: Instant::now(),
+            needs_setup: false,
+            reset_on_reload: true,
+            status: String::new(),
+        }
+    }
+
+    pub fn running(&self) -> bool {
+        self.ast.is_some()
+    }
+
+    /// Compiles the script file and schedules `setup()`.
+    pub fn load(&mut self) -> Result<()> {
+        let path = PathBuf::from(&self.path);
+        self.modified = fs::metadata(&path).and_then(|m| m.modified()).ok();
+        let source = fs::read_to_string(&path)?;
+        let ast = self
+            .engine
+            .compile(&source)
+            .map_err(|e| SandboxError::Script(e.to_string()))?;
+        self.ast = Some(ast);
+        self.state = Dynamic::from_map(Map::new());
+        self.needs_setup = true;
+        self.status = format!("Running {}", path.display());
+        log::info!("Loaded script {}", path.display());
+        Ok(())
+    }
+
+    pub fn stop(&mut self) {
+        self.ast = None;
+        self.status = "Stopped".to_string();
+    }
+
+    /// Reloads the script if the file changed.
+    fn check_reload(&mut self) -> Result<bool> {
+        if self.last_check.elapsed() < RELOAD_INTERVAL {
+            return Ok(false);
+        }
+        self.last_check = Instant::now();
+        let modified = fs::metadata(&self.path).and_then(|m| m.modified()).ok();
+        if modified.is_none() || modified == self.modified {
+            return Ok(false);
+        }
+        // The old version keeps running if the new one does not compile.
+        self.load()?;
+        Ok(true)
+    }
+
+    /// Runs `setup()` after a (re)load, then `tick(dt)`.
+    ///
+    /// A failing call stops the script, so errors are not repeated every tick.
+    pub fn update(&mut self, frame: ScriptFrame, dt: f32) -> Result<ScriptRun> {
+        let mut clear = false;
+        if self.running() && self.check_reload()? {
+            clear = self.reset_on_reload;
+        }
+        self.shared.borrow_mut().frame = frame;
+        let result = self
+            .setup()
+            .and_then(|()| self.call("tick", (dt as FLOAT,), 1));
+        let mut shared = self.shared.borrow_mut();
+        let run = ScriptRun {
+            clear: clear || std::mem::take(&mut shared.clear),
+            commands: std::mem::take(&mut shared.commands),
+        };
+        drop(shared);
+        if let Err(e) = result {
+            self.ast = None;
+            self.status = format!("Stopped after an error: {e}");
+            return Err(e);
+        }
+        Ok(run)
+    }
+
+    fn setup(&mut self) -> Result<()> {
+        if !std::mem::take(&mut self.needs_setup) {
+            return Ok(());
+        }
+        if let Some(ast) = &self.ast {
+            // Top level statements run once, before `setup()`.
+            self.engine
+                .run_ast(ast)
+                .map_err(|e| SandboxError::Script(e.to_string()))?;
+        }
+        self.call("setup", (), 0)
+    }
+
+    /// Calls a script function if the script defines it.
+    fn call(&mut self, name: &str, args: impl rhai::FuncArgs, params: usize) -> Result<()> {
+        let Some(ast) = &self.ast else {
+            return Ok(());
+        };
+        if !ast
+            .iter_functions()
+            .any(|f| f.name == name && f.params.len() == params)
+        {
+            return Ok(());
+        }
+        let options = CallFnOptions::new()
+            .eval_ast(false)
+            .bind_this_ptr(&mut self.state);
+        self.engine
+            .call_fn_with_options::<Dynamic>(options, &mut Scope::new(), ast, name, args)
+            .map_err(|e| SandboxError::Script(format!("{name}: {e}")))?;
+        Ok(())
+    }
+
+    pub fn ui(&mut self, ui: &mut egui::Ui) -> Result<()> {
+        let mut result = Ok(());
+        ui.horizontal(|ui| {
+            ui.label("Script");
+            ui.text_edit_singleline(&mut self.path);
+        });
+        ui.horizontal(|ui| {
+            if ui
+                .button(if self.running() { "Restart" } else { "Run" })
+                .clicked()
+            {
+                result = self.load();
+                if result.is_ok() {
+                    self.shared.borrow_mut().clear = self.reset_on_reload;
+                }
+            }
+            if ui
+                .add_enabled(self.running(), egui::Button::new("Stop"))
+                .clicked()
+            {
+                self.stop();
+            }
+            ui.checkbox(&mut self.reset_on_reload, "Clear scene on reload");
+        });
+        if let Err(e) = &result {
+            self.status = format!("Failed to load: {e}");
+        }
+        if !self.status.is_empty() {
+            ui.label(&self.status);
+        }
+        ui.collapsing("API", |ui| {
+            ui.label(egui::RichText::new(API).monospace());
+        });
+        result
+    }
+}
+
+fn number(value: Dynamic) -> Result<f32, Box<EvalAltResult>> {
+    if let Ok(float) = value.as_float() {
+        Ok(float as f32)
+    } else if let Ok(int) = value.as_int() {
+        Ok(int as f32)
+    } else {
+        Err(format!("expected a number, got {}", value.type_name()).into())
+    }
+}
+
+fn object_number(value: i64) -> Result<u32, Box<EvalAltResult>> {
+    u32::try_from(value).map_err(|_| format!("invalid object number {value}").into())
+}
+
+fn array(vector: Vec2) -> Array {
+    vec![
+        Dynamic::from_float(vector.x as FLOAT),
+        Dynamic::from_float(vector.y as FLOAT),
+    ]
+}
+
+fn register_api(engine: &mut Engine, shared: &Rc<RefCell<Shared>>) {
+    let spawn = {
+        let shared = shared.clone();
+        move |x: Dynamic, y: Dynamic, w: Dynamic, h: Dynamic, fixed: bool| {
+            let command = Command::Spawn {
+                position: vec2(number(x)?, number(y)?),
+                size: vec2(number(w)?, number(h)?) / 2.0,
+                body: BodySettings {
+                    fixed,
+                    ..Default::default()
+                },
+            };
+            let mut shared = shared.borrow_mut();
+            let id = shared.frame.next_number;
+            shared.frame.next_number += 1;
+            shared.commands.push(command);
+            Ok::<_, Box<EvalAltResult>>(id as i64)
+        }
+    };
+    let spawn_dynamic = spawn.clone();
+    engine.register_fn("spawn_box", spawn);
+    engine.register_fn(
+        "spawn_box",
+        move |x: Dynamic, y: Dynamic, w: Dynamic, h: Dynamic| spawn_dynamic(x, y, w, h, false),
+    );
+
+    let s = shared.clone();
+    engine.register_fn("joint", move |kind: &str, a: i64, b: i64| {
+        let kind = match kind {
+            "fixed" => JointKind::Fixed,
+            "revolute" => JointKind::Revolute,
+            other => return Err(format!("unknown joint kind `{other}`").into()),
+        };
+        s.borrow_mut().commands.push(Command::Joint {
+            kind,
+            a: object_number(a)?,
+            b: object_number(b)?,
+        });
+        Ok::<_, Box<EvalAltResult>>(())
+    });
+
+    let s = shared.clone();
+    engine.register_fn("remove", move |n: i64| {
+        s.borrow_mut()
+            .commands
+            .push(Command::Remove(object_number(n)?));
+        Ok::<_, Box<EvalAltResult>>(())
+    });
+
+    let s = shared.clone();
+    engine.register_fn("set_velocity", move |n: i64, x: Dynamic, y: Dynamic| {
+        s.borrow_mut().commands.push(Command::Velocity {
+            number: object_number(n)?,
+            velocity: vec2(number(x)?, number(y)?),
+        });
+        Ok::<_, Box<EvalAltResult>>(())
+    });
+
+    let s = shared.clone();
+    engine.register_fn("position", move |n: i64| {
+        let shared = s.borrow();
+        u32::try_from(n)
+            .ok()
+            .and_then(|n| shared.frame.objects.get(&n))
+            .map(|(position, _)| array(*position))
+            .unwrap_or_default()
+    });
+
+    let s = shared.clone();
+    engine.register_fn("velocity", move |n: i64| {
+        let shared = s.borrow();
+        u32::try_from(n)
+            .ok()
+            .and_then(|n| shared.frame.objects.get(&n))
+            .map(|(_, velocity)| array(*velocity))
+            .unwrap_or_default()
+    });
+
+    let s = shared.clone();
+    engine.register_fn("set_gravity", move |x: Dynamic, y: Dynamic| {
+        s.borrow_mut()
+            .commands
+            .push(Command::Gravity(vec2(number(x)?, number(y)?)));
+        Ok::<_, Box<EvalAltResult>>(())
+    });
+
+    let s = shared.clone();
+    engine.register_fn("set_timescale", move |scale: Dynamic| {
+        s.borrow_mut()
+            .commands
+            .push(Command::TimeScale(number(scale)?));
+        Ok::<_, Box<EvalAltResult>>(())
+    });
+
+    let s = shared.clone();
+    engine.register_fn("set_physics", move |name: &str, value: Dynamic| {
+        s.borrow_mut().commands.push(Command::Physics {
+            parameter: name.to_string(),
+            value: number(value)?,
+        });
+        Ok::<_, Box<EvalAltResult>>(())
+    });
+
+    let s = shared.clone();
+    engine.register_fn("clear", move || {
+        // Anything queued before belonged to the old scene. The numbers of the dropped
+        // spawns are handed out again.
+        let mut shared = s.borrow_mut();
+        let spawns = shared
+            .commands
+            .drain(..)
+            .filter(|command| matches!(command, Command::Spawn { .. }))
+            .count() as u32;
+        shared.frame.next_number -= spawns;
+        shared.clear = true;
+    });
+
+    let s = shared.clone();
+    engine.register_fn("cursor", move || array(s.borrow().frame.cursor));
+
+    let s = shared.clone();
+    engine.register_fn("primary_down", move || s.borrow().frame.primary);
+
+    let s = shared.clone();
+    engine.register_fn("secondary_down", move || s.borrow().frame.secondary);
+
+    let s = shared.clone();
+    engine.register_fn("key_down", move |name: &str| {
+        s.borrow()
+            .frame
+            .keys
+            .iter()
+            .any(|key| key.eq_ignore_ascii_case(name))
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn clear_hands_out_dropped_numbers_again() {
+        let scripting = Scripting::new();
+        scripting.shared.borrow_mut().frame.next_number = 5;
+        let number = scripting
+            .engine
+            .eval::<i64>("spawn_box(0, 0, 1, 1); spawn_box(0, 0, 1, 1, true); clear(); spawn_box(0, 0, 1, 1)")
+            .unwrap();
+        assert_eq!(number, 5);
+        let shared = scripting.shared.borrow();
+        assert!(shared.clear);
+        assert_eq!(shared.commands.len(), 1);
+        assert_eq!(shared.frame.next_number, 6);
+    }
+
+    #[test]
+    fn bad_arguments_queue_nothing() {
+        let scripting = Scripting::new();
+        assert!(
+            scripting
+                .engine
+                .eval::<i64>(r#"spawn_box("a", 0, 1, 1)"#)
+                .is_err()
+        );
+        let shared = scripting.shared.borrow();
+        assert!(shared.commands.is_empty());
+        assert_eq!(shared.frame.next_number, 0);
+    }
+}
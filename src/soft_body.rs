@@ -0,0 +1,289 @@
+//! Soft bodies: lattices of small circles held together by springs.
+//!
+//! The particles are spawned objects, so the tools can grab them, but a soft body is
+//! drawn as one textured mesh following them. Removing any particle removes the whole
+//! body. Soft bodies are not saved in scenes.
+
+use let_engine::prelude::{
+    gpu::{
+        VulkanTypes, buffer::BufferId, material::MaterialId, model::ModelId, texture::TextureId,
+    },
+    *,
+};
+
+use crate::{
+    Ctx,
+    error::{ObjectLookup, Result, SandboxError},
+    sandbox::Sandbox,
+};
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum SoftShape {
+    Grid,
+    Ring,
+}
+
+#[derive(Clone, Copy)]
+pub struct SoftBodySettings {
+    pub shape: SoftShape,
+    /// Particles along each side of a grid, or around a ring.
+    pub resolution: usize,
+    /// Half extent of a grid, or radius of a ring.
+    pub size: f32,
+    pub stiffness: f32,
+    pub damping: f32,
+}
+
+/// The rest shape of a soft body, relative to its center.
+pub struct Lattice {
+    pub points: Vec<Vec2>,
+    /// Texture coordinates, in the same -1 to 1 range as the box model.
+    pub uvs: Vec<Vec2>,
+    pub indices: Vec<u32>,
+    /// Pairs of points joined by a spring.
+    pub springs: Vec<(usize, usize)>,
+    pub particle_radius: f32,
+}
+
+impl SoftBodySettings {
+    pub fn lattice(&self) -> Lattice {
+        match self.shape {
+            SoftShape::Grid => self.grid(),
+            SoftShape::Ring => self.ring(),
+        }
+    }
+
+    fn grid(&self) -> Lattice {
+        let n = self.resolution.max(2);
+        let spacing = self.size * 2.0 / (n - 1) as f32;
+        let index = |x: usize, y: usize| y * n + x;
+        let mut lattice = Lattice {
+            points: Vec::with_capacity(n * n),
+            uvs: Vec::with_capacity(n * n),
+            indices: Vec::new(),
+            springs: Vec::new(),
+            particle_radius: spacing * 0.35,
+        };
+        for y in 0..n {
+            for x in 0..n {
+                let uv = vec2(x as f32, y as f32) / (n - 1) as f32 * 2.0 - 1.0;
+                lattice.points.push(uv * self.size);
+                lattice.uvs.push(uv);
+            }
+        }
+        for y in 0..n {
+            for x in 0..n {
+                if x + 1 < n {
+                    lattice.springs.push((index(x, y), index(x + 1, y)));
+                }
+                if y + 1 < n {
+                    lattice.springs.push((index(x, y), index(x, y + 1)));
+                }
+                if x + 1 < n && y + 1 < n {
+                    // Diagonals keep the cells from shearing flat.
+                    lattice.springs.push((index(x, y), index(x + 1, y + 1)));
+                    lattice.springs.push((index(x + 1, y), index(x, y + 1)));
+                    let cell = [
+                        index(x, y),
+                        index(x + 1, y),
+                        index(x, y + 1),
+                        index(x + 1, y),
+                        index(x + 1, y + 1),
+                        index(x, y + 1),
+                    ];
+                    lattice.indices.extend(cell.map(|i| i as u32));
+                }
+            }
+        }
+        lattice
+    }
+
+    /// A center particle and a ring around it.
+    fn ring(&self) -> Lattice {
+        let n = self.resolution.max(3);
+        let spacing = std::f32::consts::TAU * self.size / n as f32;
+        let mut lattice = Lattice {
+            points: vec![Vec2::ZERO],
+            uvs: vec![Vec2::ZERO],
+            indices: Vec::new(),
+            springs: Vec::new(),
+            particle_radius: (spacing * 0.35).min(self.size * 0.2),
+        };
+        for i in 0..n {
+            let uv = Vec2::from_angle(i as f32 / n as f32 * std::f32::consts::TAU);
+            lattice.points.push(uv * self.size);
+            lattice.uvs.push(uv);
+        }
+        let ring = |i: usize| 1 + i % n;
+        for i in 0..n {
+            lattice.springs.push((0, ring(i)));
+            lattice.springs.push((ring(i), ring(i + 1)));
+            // Skipping one particle resists the rim folding in. With three particles
+            // these are the rim springs, and with four each diameter comes up twice.
+            if n > 4 || (n == 4 && i < 2) {
+                lattice.springs.push((ring(i), ring(i + 2)));
+            }
+            lattice
+                .indices
+                .extend([0, ring(i) as u32, ring(i + 1) as u32]);
+        }
+        lattice
+    }
+}
+
+/// What the soft body meshes are drawn with.
+pub struct SoftStyle {
+    pub material: MaterialId<TVert>,
+    pub texture: TextureId,
+    pub color: BufferId<Color>,
+}
+
+impl SoftStyle {
+    /// Loads a mesh for the lattice and an appearance drawing it.
+    pub fn mesh(
+        &self,
+        ctx: &Ctx,
+        lattice: &Lattice,
+    ) -> Result<(ModelId<TVert>, Appearance<VulkanTypes>)> {
+        let vertices = lattice
+            .points
+            .iter()
+            .zip(&lattice.uvs)
+            .map(|(point, uv)| tvert(point.x, point.y, uv.x, uv.y))
+            .collect();
+        let model = ctx
+            .gpu
+            .load_model(&model!(vertices, lattice.indices.clone()))
+            .map_err(SandboxError::gpu)?;
+        Ok((model, self.appearance(ctx, model)?))
+    }
+
+    /// An appearance drawing a loaded mesh.
+    pub fn appearance(&self, ctx: &Ctx, model: ModelId<TVert>) -> Result<Appearance<VulkanTypes>> {
+        AppearanceBuilder::default()
+            .model(model)
+            .material(self.material)
+            .descriptors(&[
+                (Location::new(0, 0), Descriptor::Mvp),
+                (Location::new(1, 0), Descriptor::buffer(self.color)),
+                (Location::new(2, 0), Descriptor::Texture(self.texture)),
+            ])
+            .build(&ctx.gpu)
+            .map_err(SandboxError::gpu)
+    }
+}
+
+/// A spawned soft body.
+pub struct SoftBody {
+    pub particles: Vec<ObjectId>,
+    uvs: Vec<Vec2>,
+    /// The object drawing the mesh. It sits at the origin with the vertices in world space.
+    pub mesh: ObjectId,
+    model: ModelId<TVert>,
+}
+
+impl SoftBody {
+    pub fn new(
+        particles: Vec<ObjectId>,
+        lattice: Lattice,
+        mesh: ObjectId,
+        model: ModelId<TVert>,
+    ) -> Self {
+        Self {
+            particles,
+            uvs: lattice.uvs,
+            mesh,
+            model,
+        }
+    }
+
+    pub fn model(&self) -> ModelId<TVert> {
+        self.model
+    }
+}
+
+/// Moves the mesh vertices to their particles.
+pub fn update(ctx: &Ctx, sandbox: &Sandbox) -> Result<()> {
+    for body in &sandbox.soft_bodies {
+        let mut positions = Vec::with_capacity(body.particles.len());
+        for &id in &body.particles {
+            positions.push(ctx.scene.object(id).or_stale(id)?.transform.position);
+        }
+        ctx.gpu
+            .model(body.model)
+            .map_err(SandboxError::gpu)?
+            .write_vertices(
+                |vertices| {
+                    for ((vertex, position), uv) in
+                        vertices.iter_mut().zip(&positions).zip(&body.uvs)
+                    {
+                        *vertex = tvert(position.x, position.y, uv.x, uv.y);
+                    }
+                },
+                positions.len(),
+            )
+            .map_err(SandboxError::gpu)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+
+    fn lattice(shape: SoftShape, resolution: usize) -> Lattice {
+        SoftBodySettings {
+            shape,
+            resolution,
+            size: 1.0,
+            stiffness: 1.0,
+            damping: 0.0,
+        }
+        .lattice()
+    }
+
+    fn assert_unique_springs(lattice: &Lattice) {
+        let mut pairs = HashSet::new();
+        for &(a, b) in &lattice.springs {
+            assert_ne!(a, b);
+            assert!(a < lattice.points.len() && b < lattice.points.len());
+            assert!(
+                pairs.insert((a.min(b), a.max(b))),
+                "spring {a}-{b} is doubled"
+            );
+        }
+    }
+
+    #[test]
+    fn grid_springs() {
+        for n in [2, 3, 5, 8] {
+            let lattice = lattice(SoftShape::Grid, n);
+            assert_eq!(lattice.points.len(), n * n);
+            assert_eq!(
+                lattice.springs.len(),
+                2 * n * (n - 1) + 2 * (n - 1) * (n - 1)
+            );
+            assert_eq!(lattice.indices.len(), 6 * (n - 1) * (n - 1));
+            assert_unique_springs(&lattice);
+        }
+    }
+
+    #[test]
+    fn ring_springs() {
+        for (n, skips) in [(3, 0), (4, 2), (5, 5), (8, 8)] {
+            let lattice = lattice(SoftShape::Ring, n);
+            assert_eq!(lattice.points.len(), n + 1);
+            assert_eq!(lattice.springs.len(), 2 * n + skips);
+            assert_eq!(lattice.indices.len(), 3 * n);
+            assert_unique_springs(&lattice);
+        }
+    }
+
+    #[test]
+    fn resolution_is_clamped() {
+        assert_eq!(lattice(SoftShape::Grid, 0).points.len(), 4);
+        assert_eq!(lattice(SoftShape::Ring, 1).points.len(), 4);
+    }
+}
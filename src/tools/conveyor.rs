@@ -0,0 +1,103 @@
+use let_engine::prelude::*;
+
+use super::{Gizmos, Tool, ToolInput};
+use crate::{Ctx, conveyors::Belt, error::Result, sandbox::Sandbox};
+
+/// Drag from one point to another to place a conveyor belt between them.
+///
+/// With a positive speed the belt's top runs in the dragged direction.
+pub struct ConveyorTool {
+    belt: Belt,
+    /// Full thickness of the belt.
+    thickness: f32,
+    start: Option<Vec2>,
+    /// Set from the ui to give the selected belt these settings on the next update.
+    apply: bool,
+}
+
+impl ConveyorTool {
+    pub fn new() -> Self {
+        Self {
+            belt: Belt::default(),
+            thickness: 0.05,
+            start: None,
+            apply: false,
+        }
+    }
+}
+
+impl Tool for ConveyorTool {
+    fn name(&self) -> &'static str {
+        "Conveyor"
+    }
+
+    fn exit(&mut self, _ctx: &Ctx, _sandbox: &mut Sandbox) {
+        self.start = None;
+    }
+
+    fn update(&mut self, ctx: &Ctx, sandbox: &mut Sandbox, input: &ToolInput) -> Result<()> {
+        if std::mem::take(&mut self.apply)
+            && let Some(id) = sandbox.selected_object
+            && let Some(conveyor) = sandbox.conveyors.get_mut(&id)
+        {
+            conveyor.belt = self.belt;
+        }
+        if input.primary.pressed {
+            self.start = Some(input.cursor);
+        }
+        if input.primary.released
+            && let Some(start) = self.start.take()
+        {
+            let length = start.distance(input.cursor);
+            if length < self.thickness {
+                return Ok(());
+            }
+            let transform = (
+                start.midpoint(input.cursor),
+                vec2(length / 2.0, self.thickness / 2.0),
+                (input.cursor - start).to_angle(),
+            )
+                .into();
+            let id = sandbox.spawn_conveyor(ctx, transform, self.belt)?;
+            sandbox.selected_object = Some(id);
+        }
+        Ok(())
+    }
+
+    fn draw_gizmo(
+        &mut self,
+        ctx: &Ctx,
+        _sandbox: &Sandbox,
+        gizmos: &Gizmos,
+        input: &ToolInput,
+    ) -> Result<()> {
+        let Some(start) = self.start else {
+            return Ok(());
+        };
+        let length = start.distance(input.cursor);
+        gizmos.rect(
+            ctx,
+            (
+                start.midpoint(input.cursor),
+                vec2(length / 2.0, self.thickness / 2.0),
+                (input.cursor - start).to_angle(),
+            )
+                .into(),
+        )?;
+        gizmos.arrow(ctx, start, input.cursor)
+    }
+
+    fn ui(&mut self, ui: &mut egui::Ui, sandbox: &Sandbox) {
+        ui.horizontal(|ui| {
+            ui.add(egui::Slider::new(&mut self.belt.speed, -3.0..=3.0).text("Speed"));
+            ui.add(egui::Slider::new(&mut self.belt.grip, 0.01..=1.0).text("Grip"));
+            ui.add(egui::Slider::new(&mut self.thickness, 0.01..=0.3).text("Thickness"));
+            let belt_selected = sandbox
+                .selected_object
+                .is_some_and(|id| sandbox.conveyors.contains_key(&id));
+            self.apply |= ui
+                .add_enabled(belt_selected, egui::Button::new("Apply to selected"))
+                .clicked();
+        });
+    }
+}
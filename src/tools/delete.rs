@@ -0,0 +1,33 @@
+use super::{Gizmos, Tool, ToolInput};
+use crate::{Ctx, error::Result, sandbox::Sandbox};
+
+/// Removes every spawned object under the cursor.
+pub struct DeleteTool;
+
+impl Tool for DeleteTool {
+    fn name(&self) -> &'static str {
+        "Delete"
+    }
+
+    fn update(&mut self, ctx: &Ctx, sandbox: &mut Sandbox, input: &ToolInput) -> Result<()> {
+        if input.primary.pressed || input.secondary.pressed {
+            for id in sandbox.objects_at(ctx, input.cursor) {
+                sandbox.remove_object(ctx, id);
+            }
+        }
+        Ok(())
+    }
+
+    fn draw_gizmo(
+        &mut self,
+        ctx: &Ctx,
+        sandbox: &Sandbox,
+        gizmos: &Gizmos,
+        input: &ToolInput,
+    ) -> Result<()> {
+        match sandbox.object_at(ctx, input.cursor) {
+            Some(id) => gizmos.outline(ctx, id),
+            None => Ok(()),
+        }
+    }
+}
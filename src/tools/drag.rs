@@ -0,0 +1,86 @@
+use let_engine::prelude::*;
+
+use super::{Gizmos, Tool, ToolInput};
+use crate::{
+    Ctx,
+    error::{ObjectLookup, Result},
+    sandbox::Sandbox,
+};
+
+/// Grabs a dynamic body and pulls it towards the cursor.
+pub struct DragTool {
+    /// The grabbed object and the grab point relative to its center.
+    grabbed: Option<(ObjectId, Vec2)>,
+    /// Fraction of the distance to the cursor covered per tick.
+    stiffness: f32,
+}
+
+impl DragTool {
+    pub fn new() -> Self {
+        Self {
+            grabbed: None,
+            stiffness: 0.2,
+        }
+    }
+}
+
+impl Tool for DragTool {
+    fn name(&self) -> &'static str {
+        "Drag"
+    }
+
+    fn exit(&mut self, _ctx: &Ctx, _sandbox: &mut Sandbox) {
+        self.grabbed = None;
+    }
+
+    fn update(&mut self, ctx: &Ctx, sandbox: &mut Sandbox, input: &ToolInput) -> Result<()> {
+        if input.primary.pressed
+            && let Some(id) = sandbox.object_at(ctx, input.cursor)
+        {
+            let position = ctx.scene.object(id).or_stale(id)?.transform.position;
+            self.grabbed = Some((id, input.cursor - position));
+        }
+        if !input.primary.down {
+            self.grabbed = None;
+            return Ok(());
+        }
+        let Some((id, offset)) = self.grabbed else {
+            return Ok(());
+        };
+        // Let go of objects that were removed while dragging.
+        let Ok(object) = ctx.scene.object_mut(id).or_stale(id) else {
+            self.grabbed = None;
+            return Ok(());
+        };
+        let target = input.cursor - offset;
+        let velocity = (target - object.transform.position) * self.stiffness / input.dt;
+        if let Some(rigid_body) = object.rigid_body_mut() {
+            rigid_body.set_linvel(velocity.into(), true);
+        }
+        Ok(())
+    }
+
+    fn draw_gizmo(
+        &mut self,
+        ctx: &Ctx,
+        sandbox: &Sandbox,
+        gizmos: &Gizmos,
+        input: &ToolInput,
+    ) -> Result<()> {
+        match self.grabbed {
+            Some((id, offset)) => {
+                let from = ctx.scene.object(id).or_stale(id)?.transform.position + offset;
+                gizmos.outline(ctx, id)?;
+                gizmos.arrow(ctx, from, input.cursor)
+            }
+            None => match sandbox.object_at(ctx, input.cursor) {
+                Some(id) => gizmos.outline(ctx, id),
+                None => Ok(()),
+            },
+        }
+    }
+
+    fn ui(&mut self, ui: &mut egui::Ui, _sandbox: &Sandbox) {
+        ui.add(egui::Slider::new(&mut self.stiffness, 0.01..=1.0).text("Stiffness"));
+    }
+}
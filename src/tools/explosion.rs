@@ -0,0 +1,213 @@
+use std::time::{Duration, Instant};
+
+use let_engine::prelude::*;
+
+use super::{Gizmos, Tool, ToolInput};
+use crate::{
+    Ctx,
+    error::{ObjectLookup, Result},
+    sandbox::Sandbox,
+};
+
+/// How long the ring of an explosion stays visible.
+const RING_DURATION: Duration = Duration::from_millis(300);
+const BOOM: &str = "sounds/boom.mp3";
+
+#[derive(Clone, Copy, PartialEq)]
+enum Mode {
+    /// A radial impulse around the cursor.
+    Explode,
+    /// Drag from a body to push it in that direction.
+    Push,
+}
+
+/// How the impulse fades towards the edge of the radius.
+#[derive(Clone, Copy, PartialEq)]
+enum Falloff {
+    Constant,
+    Linear,
+    Quadratic,
+}
+
+impl Falloff {
+    const ALL: [Falloff; 3] = [Falloff::Constant, Falloff::Linear, Falloff::Quadratic];
+
+    fn name(self) -> &'static str {
+        match self {
+            Falloff::Constant => "Constant",
+            Falloff::Linear => "Linear",
+            Falloff::Quadratic => "Quadratic",
+        }
+    }
+
+    /// Impulse factor at `distance`, given as a fraction of the radius.
+    fn factor(self, distance: f32) -> f32 {
+        let remaining = (1.0 - distance).clamp(0.0, 1.0);
+        match self {
+            Falloff::Constant => 1.0,
+            Falloff::Linear => remaining,
+            Falloff::Quadratic => remaining * remaining,
+        }
+    }
+}
+
+/// Applies impulses to dynamic bodies: radial explosions or a directional push.
+pub struct ExplosionTool {
+    mode: Mode,
+    radius: f32,
+    strength: f32,
+    falloff: Falloff,
+    sound: bool,
+    /// Center and start of the last explosion, drawn as an expanding ring.
+    ring: Option<(Vec2, Instant)>,
+    /// The pushed object and the grab point relative to its center.
+    pushing: Option<(ObjectId, Vec2)>,
+    play: Option<&'static str>,
+}
+
+impl ExplosionTool {
+    pub fn new() -> Self {
+        Self {
+            mode: Mode::Explode,
+            radius: 0.5,
+            strength: 0.05,
+            falloff: Falloff::Linear,
+            sound: true,
+            ring: None,
+            pushing: None,
+            play: None,
+        }
+    }
+
+    fn explode(&mut self, ctx: &Ctx, sandbox: &Sandbox, center: Vec2) -> Result<()> {
+        for id in sandbox.objects_in_circle(ctx, center, self.radius) {
+            let object = ctx.scene.object_mut(id).or_stale(id)?;
+            let offset = object.transform.position - center;
+            let distance = offset.length();
+            // A body right at the center is pushed upwards.
+            let direction = offset.try_normalize().unwrap_or(vec2(0.0, -1.0));
+            let impulse = direction * self.strength * self.falloff.factor(distance / self.radius);
+            if let Some(body) = object.rigid_body_mut()
+                && body.is_dynamic()
+            {
+                body.apply_impulse(impulse.into(), true);
+            }
+        }
+        self.ring = Some((center, Instant::now()));
+        if self.sound {
+            self.play = Some(BOOM);
+        }
+        Ok(())
+    }
+
+    fn push(&mut self, ctx: &Ctx, input: &ToolInput) -> Result<()> {
+        let Some((id, offset)) = self.pushing.take() else {
+            return Ok(());
+        };
+        let Ok(object) = ctx.scene.object_mut(id).or_stale(id) else {
+            return Ok(());
+        };
+        let point = object.transform.position + offset;
+        // The drag vector, scaled so a drag across the radius gives the full strength.
+        let impulse = (input.cursor - point) / self.radius * self.strength;
+        if let Some(body) = object.rigid_body_mut()
+            && body.is_dynamic()
+        {
+            body.apply_impulse_at_point(impulse.into(), point.into(), true);
+        }
+        Ok(())
+    }
+}
+
+impl Tool for ExplosionTool {
+    fn name(&self) -> &'static str {
+        "Explosion"
+    }
+
+    fn exit(&mut self, _ctx: &Ctx, _sandbox: &mut Sandbox) {
+        self.pushing = None;
+    }
+
+    fn update(&mut self, ctx: &Ctx, sandbox: &mut Sandbox, input: &ToolInput) -> Result<()> {
+        match self.mode {
+            Mode::Explode if input.primary.pressed => self.explode(ctx, sandbox, input.cursor)?,
+            Mode::Explode => (),
+            Mode::Push => {
+                if input.primary.pressed
+                    && let Some(id) = sandbox.object_at(ctx, input.cursor)
+                {
+                    let position = ctx.scene.object(id).or_stale(id)?.transform.position;
+                    self.pushing = Some((id, input.cursor - position));
+                }
+                if input.primary.released {
+                    self.push(ctx, input)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn draw_gizmo(
+        &mut self,
+        ctx: &Ctx,
+        _sandbox: &Sandbox,
+        gizmos: &Gizmos,
+        input: &ToolInput,
+    ) -> Result<()> {
+        if let Some((center, start)) = self.ring {
+            let progress = start.elapsed().as_secs_f32() / RING_DURATION.as_secs_f32();
+            if progress < 1.0 {
+                return gizmos.ring(ctx, center, self.radius * progress);
+            }
+            self.ring = None;
+        }
+        match (self.mode, self.pushing) {
+            (Mode::Explode, _) => gizmos.ring(ctx, input.cursor, self.radius),
+            (Mode::Push, Some((id, offset))) => {
+                let from = ctx.scene.object(id).or_stale(id)?.transform.position + offset;
+                gizmos.outline(ctx, id)?;
+                gizmos.arrow(ctx, from, input.cursor)
+            }
+            (Mode::Push, None) => Ok(()),
+        }
+    }
+
+    fn sound(&mut self) -> Option<&'static str> {
+        self.play.take()
+    }
+
+    fn ui(&mut self, ui: &mut egui::Ui, _sandbox: &Sandbox) {
+        ui.horizontal(|ui| {
+            ui.radio_value(&mut self.mode, Mode::Explode, "Explode");
+            ui.radio_value(&mut self.mode, Mode::Push, "Push");
+            ui.add(egui::Slider::new(&mut self.radius, 0.05..=3.0).text("Radius"));
+            ui.add(
+                egui::Slider::new(&mut self.strength, 0.001..=1.0)
+                    .logarithmic(true)
+                    .text("Strength"),
+            );
+            egui::ComboBox::from_label("Falloff")
+                .selected_text(self.falloff.name())
+                .show_ui(ui, |ui| {
+                    for falloff in Falloff::ALL {
+                        ui.selectable_value(&mut self.falloff, falloff, falloff.name());
+                    }
+                });
+            ui.checkbox(&mut self.sound, "Sound");
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn falloff_factors() {
+        let factors = |distance| Falloff::ALL.map(|falloff| falloff.factor(distance));
+        assert_eq!(factors(0.0), [1.0, 1.0, 1.0]);
+        assert_eq!(factors(0.5), [1.0, 0.5, 0.25]);
+        assert_eq!(factors(1.0), [1.0, 0.0, 0.0]);
+        assert_eq!(factors(1.5), [1.0, 0.0, 0.0]);
+    }
+}
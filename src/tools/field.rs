@@ -0,0 +1,74 @@
+use let_engine::prelude::*;
+
+use super::{Gizmos, Tool, ToolInput};
+use crate::{Ctx, error::Result, fields::FieldKind, sandbox::Sandbox};
+
+/// Places force fields. The secondary button removes the field under the cursor.
+pub struct FieldTool {
+    kind: FieldKind,
+    /// Half extents, or the radius in `x` for radial fields.
+    size: Vec2,
+}
+
+impl FieldTool {
+    pub fn new() -> Self {
+        Self {
+            kind: FieldKind::DEFAULTS[0],
+            size: vec2(0.5, 0.3),
+        }
+    }
+}
+
+impl Tool for FieldTool {
+    fn name(&self) -> &'static str {
+        "Field"
+    }
+
+    fn update(&mut self, ctx: &Ctx, sandbox: &mut Sandbox, input: &ToolInput) -> Result<()> {
+        if input.primary.pressed {
+            let transform = (input.cursor, self.size, 0.0).into();
+            sandbox.selected_object = Some(sandbox.spawn_field(ctx, self.kind, transform)?);
+        }
+        if input.secondary.pressed {
+            for id in sandbox.objects_at(ctx, input.cursor) {
+                if sandbox.fields.contains_key(&id) {
+                    sandbox.remove_object(ctx, id);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn draw_gizmo(
+        &mut self,
+        ctx: &Ctx,
+        _sandbox: &Sandbox,
+        gizmos: &Gizmos,
+        input: &ToolInput,
+    ) -> Result<()> {
+        if self.kind.radial() {
+            gizmos.ring(ctx, input.cursor, self.size.x)
+        } else {
+            gizmos.rect(ctx, (input.cursor, self.size, 0.0).into())
+        }
+    }
+
+    fn ui(&mut self, ui: &mut egui::Ui, _sandbox: &Sandbox) {
+        ui.horizontal(|ui| {
+            for kind in FieldKind::DEFAULTS {
+                if ui
+                    .selectable_label(self.kind.name() == kind.name(), kind.name())
+                    .clicked()
+                {
+                    self.kind = kind;
+                }
+            }
+            if self.kind.radial() {
+                ui.add(egui::Slider::new(&mut self.size.x, 0.05..=5.0).text("Radius"));
+            } else {
+                ui.add(egui::Slider::new(&mut self.size.x, 0.05..=5.0).text("Size X"));
+                ui.add(egui::Slider::new(&mut self.size.y, 0.05..=5.0).text("Size Y"));
+            }
+        });
+    }
+}
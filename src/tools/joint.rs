@@ -0,0 +1,108 @@
+use let_engine::prelude::*;
+
+use super::{Gizmos, Tool, ToolInput};
+use crate::{
+    Ctx,
+    error::{ObjectLookup, Result},
+    sandbox::{BreakLimits, JointKind, Sandbox},
+};
+
+/// Drag from one object to another to weld them together with a fixed joint.
+pub struct JointTool {
+    targeted_object: Option<ObjectId>,
+    limits: BreakLimits,
+}
+
+impl JointTool {
+    pub fn new() -> Self {
+        Self {
+            targeted_object: None,
+            limits: BreakLimits::default(),
+        }
+    }
+}
+
+/// A checkbox enabling a limit, and its value.
+fn limit_ui(ui: &mut egui::Ui, label: &str, limit: &mut Option<f32>, default: f32) {
+    let mut enabled = limit.is_some();
+    ui.checkbox(&mut enabled, label);
+    let mut value = limit.unwrap_or(default);
+    ui.add_enabled(
+        enabled,
+        egui::DragValue::new(&mut value)
+            .speed(value * 0.01 + 0.01)
+            .range(0.0..=f32::MAX),
+    );
+    *limit = enabled.then_some(value);
+}
+
+impl Tool for JointTool {
+    fn name(&self) -> &'static str {
+        "Joint"
+    }
+
+    fn exit(&mut self, _ctx: &Ctx, _sandbox: &mut Sandbox) {
+        self.targeted_object = None;
+    }
+
+    fn update(&mut self, ctx: &Ctx, sandbox: &mut Sandbox, input: &ToolInput) -> Result<()> {
+        if input.primary.pressed {
+            sandbox.selected_object = sandbox.object_at(ctx, input.cursor);
+        }
+        if input.primary.down {
+            self.targeted_object = sandbox.object_at(ctx, input.cursor);
+        }
+        if input.primary.released
+            && let (Some(id), Some(target_id)) =
+                (sandbox.selected_object, self.targeted_object.take())
+            && id != target_id
+        {
+            let object = ctx.scene.object(id).or_stale(id)?;
+            let target_object = ctx.scene.object(target_id).or_stale(target_id)?;
+            let anchor = target_object.transform.position - object.transform.position;
+            sandbox.add_joint(
+                ctx,
+                (id, target_id),
+                JointKind::Fixed,
+                (anchor, vec2(0.0, 0.0)),
+                self.limits,
+            )?;
+        }
+        Ok(())
+    }
+
+    fn draw_gizmo(
+        &mut self,
+        ctx: &Ctx,
+        sandbox: &Sandbox,
+        gizmos: &Gizmos,
+        input: &ToolInput,
+    ) -> Result<()> {
+        let Some(id) = sandbox.selected_object else {
+            return Ok(());
+        };
+        gizmos.outline(ctx, id)?;
+        if input.primary.down {
+            let from = ctx.scene.object(id).or_stale(id)?.transform.position;
+            let to = match self.targeted_object {
+                Some(target) => {
+                    ctx.scene
+                        .object(target)
+                        .or_stale(target)?
+                        .transform
+                        .position
+                }
+                None => input.cursor,
+            };
+            gizmos.arrow(ctx, from, to)?;
+        }
+        Ok(())
+    }
+
+    fn ui(&mut self, ui: &mut egui::Ui, _sandbox: &Sandbox) {
+        ui.horizontal(|ui| {
+            limit_ui(ui, "Break force", &mut self.limits.force, 10.0);
+            limit_ui(ui, "Break torque", &mut self.limits.torque, 1.0);
+        });
+    }
+}
@@ -0,0 +1,311 @@
+//! The sandbox tools. Each tool lives in its own module and is registered in [`Tools::new`].
+
+mod conveyor;
+mod delete;
+mod drag;
+mod explosion;
+mod field;
+mod joint;
+mod pan;
+mod path;
+mod rope;
+mod select;
+mod soft_body;
+mod spawn;
+mod vehicle;
+
+use let_engine::prelude::{gpu::model::ModelId, *};
+
+pub use pan::Pan;
+
+use crate::{
+    Ctx, angle_between,
+    bindings::Action,
+    error::{ObjectLookup, Result, SandboxError},
+    paths::MAX_WAYPOINTS,
+    sandbox::Sandbox,
+};
+
+/// State of a tool button for the current tick.
+#[derive(Clone, Copy, Default)]
+pub struct ButtonState {
+    pub down: bool,
+    pub pressed: bool,
+    pub released: bool,
+}
+
+impl ButtonState {
+    fn next(self, down: bool) -> Self {
+        Self {
+            down,
+            pressed: down && !self.down,
+            released: !down && self.down,
+        }
+    }
+}
+
+/// Input passed to the active tool every tick.
+#[derive(Clone, Copy, Default)]
+pub struct ToolInput {
+    /// Cursor position in world space.
+    pub cursor: Vec2,
+    pub primary: ButtonState,
+    pub secondary: ButtonState,
+    /// Seconds per tick.
+    pub dt: f32,
+}
+
+/// A sandbox tool.
+///
+/// Only the active tool receives updates. Gizmos are hidden before every tick,
+/// so `draw_gizmo` has to show whatever the tool needs each time.
+pub trait Tool {
+    fn name(&self) -> &'static str;
+
+    fn enter(&mut self, _ctx: &Ctx, _sandbox: &mut Sandbox) {}
+
+    fn exit(&mut self, _ctx: &Ctx, _sandbox: &mut Sandbox) {}
+
+    fn update(&mut self, ctx: &Ctx, sandbox: &mut Sandbox, input: &ToolInput) -> Result<()>;
+
+    fn draw_gizmo(
+        &mut self,
+        _ctx: &Ctx,
+        _sandbox: &Sandbox,
+        _gizmos: &Gizmos,
+        _input: &ToolInput,
+    ) -> Result<()> {
+        Ok(())
+    }
+
+    /// An asset path of a sound to play after this tick's update.
+    fn sound(&mut self) -> Option<&'static str> {
+        None
+    }
+
+    /// Tool settings shown under the toolbar.
+    fn ui(&mut self, _ui: &mut egui::Ui, _sandbox: &Sandbox) {}
+}
+
+/// Indicator objects the tools draw with.
+pub struct Gizmos {
+    pub rect: ObjectId,
+    pub arrow: ObjectId,
+    pub arrow_model: ModelId<Vec2>,
+    /// A unit circle.
+    pub ring: ObjectId,
+    pub path: ObjectId,
+    /// A line strip of [`Gizmos::PATH_VERTICES`] points.
+    pub path_model: ModelId<Vec2>,
+}
+
+impl Gizmos {
+    /// Room for a full path, closed or with a point at the cursor.
+    pub const PATH_VERTICES: usize = MAX_WAYPOINTS + 1;
+
+    fn hide(&self, ctx: &Ctx) -> Result<()> {
+        for id in [self.rect, self.arrow, self.ring, self.path] {
+            ctx.scene
+                .object_mut(id)
+                .or_stale(id)?
+                .appearance
+                .set_visible(false);
+        }
+        Ok(())
+    }
+
+    /// Outlines a rectangle with the given transform.
+    pub fn rect(&self, ctx: &Ctx, transform: Transform) -> Result<()> {
+        let rect = ctx.scene.object_mut(self.rect).or_stale(self.rect)?;
+        rect.appearance.set_visible(true);
+        rect.transform = Transform::with_position_rotation(transform.position, transform.rotation);
+        rect.appearance.transform_mut().size = transform.size;
+        Ok(())
+    }
+
+    /// Outlines the given object.
+    pub fn outline(&self, ctx: &Ctx, id: ObjectId) -> Result<()> {
+        let object = ctx.scene.object(id).or_stale(id)?;
+        let mut transform = object.transform;
+        transform.size *= object.appearance.transform().size;
+        self.rect(ctx, transform)
+    }
+
+    /// Draws a circle around `center`.
+    pub fn ring(&self, ctx: &Ctx, center: Vec2, radius: f32) -> Result<()> {
+        let ring = ctx.scene.object_mut(self.ring).or_stale(self.ring)?;
+        ring.appearance.set_visible(true);
+        ring.transform = Transform::with_position(center);
+        ring.appearance.transform_mut().size = Vec2::splat(radius);
+        Ok(())
+    }
+
+    /// Draws an arrow pointing from `from` to `to`.
+    pub fn arrow(&self, ctx: &Ctx, from: Vec2, to: Vec2) -> Result<()> {
+        let length = from.distance(to);
+        if length == 0.0 {
+            return Ok(());
+        }
+        ctx.gpu
+            .model(self.arrow_model)
+            .map_err(SandboxError::gpu)?
+            .write_vertices(
+                |vertices| {
+                    vertices[1..3].copy_from_slice(&[
+                        vec2(length, 0.0),
+                        vec2(length - 0.05, 0.02),
+                        vec2(length - 0.05, -0.02),
+                    ]);
+                },
+                4,
+            )
+            .map_err(SandboxError::gpu)?;
+        let arrow = ctx.scene.object_mut(self.arrow).or_stale(self.arrow)?;
+        arrow.appearance.set_visible(true);
+        arrow.transform.position = from;
+        arrow.transform.rotation = angle_between(from, to);
+        Ok(())
+    }
+
+    /// Draws a line through the points. Points beyond [`Gizmos::PATH_VERTICES`] are cut off.
+    pub fn path(&self, ctx: &Ctx, points: &[Vec2]) -> Result<()> {
+        let Some(&last) = points.last() else {
+            return Ok(());
+        };
+        ctx.gpu
+            .model(self.path_model)
+            .map_err(SandboxError::gpu)?
+            .write_vertices(
+                |vertices| {
+                    // Unused vertices collapse onto the last point.
+                    for (index, vertex) in vertices.iter_mut().enumerate() {
+                        *vertex = points.get(index).copied().unwrap_or(last);
+                    }
+                },
+                Self::PATH_VERTICES,
+            )
+            .map_err(SandboxError::gpu)?;
+        let path = ctx.scene.object_mut(self.path).or_stale(self.path)?;
+        path.appearance.set_visible(true);
+        Ok(())
+    }
+}
+
+/// The registered tools and which one is active.
+pub struct Tools {
+    tools: Vec<(Action, Box<dyn Tool>)>,
+    active: usize,
+    input: ToolInput,
+    gizmos: Gizmos,
+    sound: Option<&'static str>,
+}
+
+impl Tools {
+    pub fn new(gizmos: Gizmos) -> Self {
+        Self {
+            tools: vec![
+                register(Action::SpawnTool, spawn::SpawnTool::new()),
+                register(Action::DeleteTool, delete::DeleteTool),
+                register(Action::SelectTool, select::SelectTool),
+                register(Action::JointTool, joint::JointTool::new()),
+                register(Action::DragTool, drag::DragTool::new()),
+                register(Action::PanTool, pan::PanTool::new()),
+                register(Action::ExplosionTool, explosion::ExplosionTool::new()),
+                register(Action::FieldTool, field::FieldTool::new()),
+                register(Action::RopeTool, rope::RopeTool::new()),
+                register(Action::SoftBodyTool, soft_body::SoftBodyTool::new()),
+                register(Action::VehicleTool, vehicle::VehicleTool::new()),
+                register(Action::PathTool, path::PathTool::new()),
+                register(Action::ConveyorTool, conveyor::ConveyorTool::new()),
+            ],
+            active: 0,
+            input: ToolInput::default(),
+            gizmos,
+            sound: None,
+        }
+    }
+
+    pub fn active_name(&self) -> &'static str {
+        self.tools[self.active].1.name()
+    }
+
+    pub fn switch(&mut self, ctx: &Ctx, sandbox: &mut Sandbox, index: usize) {
+        if index == self.active || index >= self.tools.len() {
+            return;
+        }
+        self.tools[self.active].1.exit(ctx, sandbox);
+        self.active = index;
+        self.tools[self.active].1.enter(ctx, sandbox);
+    }
+
+    /// Switches to the tool bound to the given hotkey action, if any.
+    pub fn hotkey(&mut self, ctx: &Ctx, sandbox: &mut Sandbox, action: Action) {
+        if action == Action::NextTool {
+            let next = (self.active + 1) % self.tools.len();
+            self.switch(ctx, sandbox, next);
+        } else if let Some(index) = self.tools.iter().position(|(a, _)| *a == action) {
+            self.switch(ctx, sandbox, index);
+        }
+    }
+
+    /// Updates the active tool, then draws its gizmos.
+    ///
+    /// With `use_input` false, for when egui has the pointer, the tool only learns about
+    /// released buttons, so drags started in the world still end.
+    pub fn update(
+        &mut self,
+        ctx: &Ctx,
+        sandbox: &mut Sandbox,
+        cursor: Vec2,
+        dt: f32,
+        (primary, secondary): (bool, bool),
+        use_input: bool,
+    ) -> Result<()> {
+        self.input = ToolInput {
+            cursor,
+            dt,
+            primary: self.input.primary.next(primary),
+            secondary: self.input.secondary.next(secondary),
+        };
+        let tool = &mut self.tools[self.active].1;
+        if use_input {
+            tool.update(ctx, sandbox, &self.input)?;
+            self.sound = tool.sound();
+        } else if self.input.primary.released || self.input.secondary.released {
+            let release = |button: ButtonState| ButtonState {
+                pressed: false,
+                ..button
+            };
+            let input = ToolInput {
+                primary: release(self.input.primary),
+                secondary: release(self.input.secondary),
+                ..self.input
+            };
+            tool.update(ctx, sandbox, &input)?;
+            self.sound = tool.sound();
+        }
+        self.gizmos.hide(ctx)?;
+        tool.draw_gizmo(ctx, sandbox, &self.gizmos, &self.input)
+    }
+
+    /// The sound the active tool asked for during the last update.
+    pub fn take_sound(&mut self) -> Option<&'static str> {
+        self.sound.take()
+    }
+
+    pub fn ui(&mut self, ui: &mut egui::Ui, ctx: &Ctx, sandbox: &mut Sandbox) {
+        let names: Vec<_> = self.tools.iter().map(|(_, tool)| tool.name()).collect();
+        ui.horizontal(|ui| {
+            for (index, name) in names.into_iter().enumerate() {
+                if ui.selectable_label(index == self.active, name).clicked() {
+                    self.switch(ctx, sandbox, index);
+                }
+            }
+        });
+        self.tools[self.active].1.ui(ui, sandbox);
+    }
+}
+
+fn register(hotkey: Action, tool: impl Tool + 'static) -> (Action, Box<dyn Tool>) {
+    (hotkey, Box::new(tool))
+}
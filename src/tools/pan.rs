@@ -0,0 +1,58 @@
+use let_engine::prelude::*;
+
+use super::{Tool, ToolInput};
+use crate::{Ctx, error::Result, sandbox::Sandbox};
+
+/// Drags the camera with the cursor while held.
+pub struct Pan {
+    /// Cursor and camera position when the drag started.
+    lock: Option<(Vec2, Vec2)>,
+}
+
+impl Pan {
+    pub fn new() -> Self {
+        Self { lock: None }
+    }
+
+    pub fn update(&mut self, ctx: &Ctx, down: bool) {
+        if !down {
+            self.lock = None;
+            return;
+        }
+        let camera = ctx.scene.root_view().camera();
+        let cp = ctx.input.scaled_cursor(CameraScaling::Expand);
+        let (mouse_lock, camera_lock) = *self.lock.get_or_insert((cp, camera.position));
+        let shift = vec2(
+            (mouse_lock[0] - cp[0]) * camera.size.x + camera_lock[0],
+            (mouse_lock[1] - cp[1]) * camera.size.y + camera_lock[1],
+        );
+        //times camera mode please
+        ctx.scene.root_view_mut().camera_mut().position = shift;
+    }
+}
+
+/// Pans the camera with the primary button.
+pub struct PanTool {
+    pan: Pan,
+}
+
+impl PanTool {
+    pub fn new() -> Self {
+        Self { pan: Pan::new() }
+    }
+}
+
+impl Tool for PanTool {
+    fn name(&self) -> &'static str {
+        "Pan"
+    }
+
+    fn exit(&mut self, _ctx: &Ctx, _sandbox: &mut Sandbox) {
+        self.pan = Pan::new();
+    }
+
+    fn update(&mut self, ctx: &Ctx, _sandbox: &mut Sandbox, input: &ToolInput) -> Result<()> {
+        self.pan.update(ctx, input.primary.down);
+        Ok(())
+    }
+}
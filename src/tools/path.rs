@@ -0,0 +1,147 @@
+use let_engine::prelude::*;
+
+use super::{Gizmos, Tool, ToolInput};
+use crate::{
+    Ctx,
+    error::{ObjectLookup, Result},
+    paths::{MAX_WAYPOINTS, Path, PathMode},
+    sandbox::Sandbox,
+};
+
+/// Click an object, then click waypoints and right click to send it along them.
+///
+/// The object becomes kinematic, carrying whatever rests on it. Right clicking an object
+/// without drawing takes its path away.
+pub struct PathTool {
+    /// The object being given a path and the waypoints so far, starting at the object.
+    drawing: Option<(ObjectId, Vec<Vec2>)>,
+    mode: PathMode,
+    speed: f32,
+    /// Set from the ui to change the selected object's path on the next update.
+    apply: bool,
+    remove: bool,
+}
+
+impl PathTool {
+    pub fn new() -> Self {
+        Self {
+            drawing: None,
+            mode: PathMode::PingPong,
+            speed: 0.3,
+            apply: false,
+            remove: false,
+        }
+    }
+
+    fn finish(&mut self, ctx: &Ctx, sandbox: &mut Sandbox) -> Result<()> {
+        let Some((id, waypoints)) = self.drawing.take() else {
+            return Ok(());
+        };
+        if waypoints.len() < 2 {
+            return Ok(());
+        }
+        sandbox.set_path(ctx, id, Path::new(waypoints, self.mode, self.speed))
+    }
+}
+
+impl Tool for PathTool {
+    fn name(&self) -> &'static str {
+        "Path"
+    }
+
+    fn exit(&mut self, _ctx: &Ctx, _sandbox: &mut Sandbox) {
+        self.drawing = None;
+    }
+
+    fn update(&mut self, ctx: &Ctx, sandbox: &mut Sandbox, input: &ToolInput) -> Result<()> {
+        // Drop the drawing if its object was removed meanwhile.
+        if let Some((id, _)) = &self.drawing
+            && !sandbox.spawned_objects.contains(id)
+        {
+            self.drawing = None;
+        }
+        if let Some(id) = sandbox.selected_object {
+            if std::mem::take(&mut self.apply)
+                && let Some(path) = sandbox.paths.get_mut(&id)
+            {
+                path.mode = self.mode;
+                path.speed = self.speed;
+            }
+            if std::mem::take(&mut self.remove) {
+                sandbox.remove_path(ctx, id)?;
+            }
+        }
+
+        if input.primary.pressed {
+            match &mut self.drawing {
+                Some((_, waypoints)) => {
+                    if waypoints.len() < MAX_WAYPOINTS {
+                        waypoints.push(input.cursor);
+                    }
+                }
+                None => {
+                    if let Some(id) = sandbox.object_at(ctx, input.cursor) {
+                        let position = ctx.scene.object(id).or_stale(id)?.transform.position;
+                        sandbox.selected_object = Some(id);
+                        self.drawing = Some((id, vec![position]));
+                    }
+                }
+            }
+        }
+        if input.secondary.pressed {
+            if self.drawing.is_some() {
+                self.finish(ctx, sandbox)?;
+            } else if let Some(id) = sandbox.object_at(ctx, input.cursor) {
+                sandbox.remove_path(ctx, id)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn draw_gizmo(
+        &mut self,
+        ctx: &Ctx,
+        sandbox: &Sandbox,
+        gizmos: &Gizmos,
+        input: &ToolInput,
+    ) -> Result<()> {
+        if let Some((id, waypoints)) = &self.drawing {
+            gizmos.outline(ctx, *id)?;
+            let mut points = waypoints.clone();
+            if points.len() < MAX_WAYPOINTS {
+                points.push(input.cursor);
+            }
+            return gizmos.path(ctx, &points);
+        }
+        let Some(id) = sandbox.selected_object else {
+            return Ok(());
+        };
+        gizmos.outline(ctx, id)?;
+        match sandbox.paths.get(&id) {
+            Some(path) => gizmos.path(ctx, &path.outline()),
+            None => Ok(()),
+        }
+    }
+
+    fn ui(&mut self, ui: &mut egui::Ui, sandbox: &Sandbox) {
+        ui.horizontal(|ui| {
+            ui.radio_value(&mut self.mode, PathMode::PingPong, "Ping-pong");
+            ui.radio_value(&mut self.mode, PathMode::Loop, "Loop");
+            ui.add(egui::Slider::new(&mut self.speed, 0.01..=3.0).text("Speed"));
+            let has_path = sandbox
+                .selected_object
+                .is_some_and(|id| sandbox.paths.contains_key(&id));
+            ui.add_enabled_ui(has_path, |ui| {
+                self.apply |= ui.button("Apply to selected").clicked();
+                self.remove |= ui.button("Remove path").clicked();
+            });
+            match &self.drawing {
+                Some((_, waypoints)) => ui.label(format!(
+                    "{}/{MAX_WAYPOINTS} waypoints. Right click to finish.",
+                    waypoints.len()
+                )),
+                None => ui.label("Click an object to draw its path."),
+            };
+        });
+    }
+}
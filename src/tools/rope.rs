@@ -0,0 +1,169 @@
+use let_engine::prelude::*;
+
+use super::{Gizmos, Tool, ToolInput};
+use crate::{
+    Ctx,
+    error::{ObjectLookup, Result},
+    sandbox::{BodySettings, BreakLimits, JointKind, Sandbox},
+};
+
+#[derive(Clone, Copy, PartialEq)]
+enum Mode {
+    /// Links hinged end to end.
+    Chain,
+    /// Shorter links held together by rope joints, so the rope has some slack.
+    Rope,
+}
+
+/// Drag from one point to another to hang a chain or rope of boxes between them.
+///
+/// Ends starting or stopping on an object are attached to it.
+pub struct RopeTool {
+    mode: Mode,
+    segments: usize,
+    /// Full thickness of the links.
+    thickness: f32,
+    stiffness: f32,
+    damping: f32,
+    attach: bool,
+    start: Option<Vec2>,
+}
+
+impl RopeTool {
+    pub fn new() -> Self {
+        Self {
+            mode: Mode::Chain,
+            segments: 12,
+            thickness: 0.02,
+            stiffness: 0.0,
+            damping: 0.0,
+            attach: true,
+            start: None,
+        }
+    }
+
+    fn build(&self, ctx: &Ctx, sandbox: &mut Sandbox, from: Vec2, to: Vec2) -> Result<()> {
+        let length = from.distance(to);
+        if length < self.thickness {
+            return Ok(());
+        }
+        let rotation = (to - from).to_angle();
+        let direction = (to - from) / length;
+        let spacing = length / self.segments as f32;
+        // Rope links leave a gap the rope joints can stretch over.
+        let (half_length, kind) = match self.mode {
+            Mode::Chain => (
+                spacing / 2.0,
+                JointKind::Hinge {
+                    stiffness: self.stiffness,
+                    damping: self.damping,
+                },
+            ),
+            Mode::Rope => (
+                spacing * 0.4,
+                JointKind::Rope {
+                    length: spacing * 0.2,
+                },
+            ),
+        };
+        let size = vec2(half_length, self.thickness / 2.0);
+        let (start, end) = (vec2(-half_length, 0.0), vec2(half_length, 0.0));
+
+        // Look up the end objects before the links are in the way.
+        let attached = |point| self.attach.then(|| sandbox.object_at(ctx, point)).flatten();
+        let (first_object, last_object) = (attached(from), attached(to));
+
+        let mut links = Vec::with_capacity(self.segments);
+        for i in 0..self.segments {
+            let center = from + direction * spacing * (i as f32 + 0.5);
+            let link = sandbox.spawn_square(
+                ctx,
+                (center, size, rotation).into(),
+                BodySettings::default(),
+            )?;
+            if let Some(&previous) = links.last() {
+                sandbox.add_joint(
+                    ctx,
+                    (previous, link),
+                    kind,
+                    (end, start),
+                    BreakLimits::default(),
+                )?;
+            }
+            links.push(link);
+        }
+
+        let ends = [
+            (first_object, links[0], from, start),
+            (last_object, links[links.len() - 1], to, end),
+        ];
+        for (object, link, point, link_anchor) in ends {
+            let Some(object) = object else {
+                continue;
+            };
+            let transform = ctx.scene.object(object).or_stale(object)?.transform;
+            let anchor = Vec2::from_angle(-transform.rotation).rotate(point - transform.position);
+            sandbox.add_joint(
+                ctx,
+                (object, link),
+                kind,
+                (anchor, link_anchor),
+                BreakLimits::default(),
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl Tool for RopeTool {
+    fn name(&self) -> &'static str {
+        "Rope"
+    }
+
+    fn exit(&mut self, _ctx: &Ctx, _sandbox: &mut Sandbox) {
+        self.start = None;
+    }
+
+    fn update(&mut self, ctx: &Ctx, sandbox: &mut Sandbox, input: &ToolInput) -> Result<()> {
+        if input.primary.pressed {
+            self.start = Some(input.cursor);
+        }
+        if input.primary.released
+            && let Some(start) = self.start.take()
+        {
+            self.build(ctx, sandbox, start, input.cursor)?;
+        }
+        Ok(())
+    }
+
+    fn draw_gizmo(
+        &mut self,
+        ctx: &Ctx,
+        _sandbox: &Sandbox,
+        gizmos: &Gizmos,
+        input: &ToolInput,
+    ) -> Result<()> {
+        match self.start {
+            Some(start) => gizmos.arrow(ctx, start, input.cursor),
+            None => Ok(()),
+        }
+    }
+
+    fn ui(&mut self, ui: &mut egui::Ui, _sandbox: &Sandbox) {
+        ui.horizontal(|ui| {
+            ui.radio_value(&mut self.mode, Mode::Chain, "Chain");
+            ui.radio_value(&mut self.mode, Mode::Rope, "Rope");
+            ui.add(egui::Slider::new(&mut self.segments, 2..=60).text("Segments"));
+            ui.add(egui::Slider::new(&mut self.thickness, 0.005..=0.2).text("Thickness"));
+            ui.add_enabled_ui(self.mode == Mode::Chain, |ui| {
+                ui.add(
+                    egui::Slider::new(&mut self.stiffness, 0.0..=1.0)
+                        .logarithmic(true)
+                        .text("Stiffness"),
+                );
+                ui.add(egui::Slider::new(&mut self.damping, 0.0..=1.0).text("Damping"));
+            });
+            ui.checkbox(&mut self.attach, "Attach ends");
+        });
+    }
+}
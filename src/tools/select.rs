@@ -0,0 +1,31 @@
+use super::{Gizmos, Tool, ToolInput};
+use crate::{Ctx, error::Result, sandbox::Sandbox};
+
+/// Selects the object under the cursor, or clears the selection when clicking nothing.
+pub struct SelectTool;
+
+impl Tool for SelectTool {
+    fn name(&self) -> &'static str {
+        "Select"
+    }
+
+    fn update(&mut self, ctx: &Ctx, sandbox: &mut Sandbox, input: &ToolInput) -> Result<()> {
+        if input.primary.pressed {
+            sandbox.selected_object = sandbox.object_at(ctx, input.cursor);
+        }
+        Ok(())
+    }
+
+    fn draw_gizmo(
+        &mut self,
+        ctx: &Ctx,
+        sandbox: &Sandbox,
+        gizmos: &Gizmos,
+        _input: &ToolInput,
+    ) -> Result<()> {
+        match sandbox.selected_object {
+            Some(id) => gizmos.outline(ctx, id),
+            None => Ok(()),
+        }
+    }
+}
@@ -0,0 +1,81 @@
+use let_engine::prelude::*;
+
+use super::{Gizmos, Tool, ToolInput};
+use crate::{
+    Ctx,
+    error::Result,
+    sandbox::Sandbox,
+    soft_body::{SoftBodySettings, SoftShape},
+};
+
+/// Spawns jelly-like soft bodies at the cursor.
+pub struct SoftBodyTool {
+    settings: SoftBodySettings,
+}
+
+impl SoftBodyTool {
+    pub fn new() -> Self {
+        Self {
+            settings: SoftBodySettings {
+                shape: SoftShape::Grid,
+                resolution: 5,
+                size: 0.15,
+                stiffness: 0.5,
+                damping: 0.01,
+            },
+        }
+    }
+}
+
+impl Tool for SoftBodyTool {
+    fn name(&self) -> &'static str {
+        "Soft body"
+    }
+
+    fn update(&mut self, ctx: &Ctx, sandbox: &mut Sandbox, input: &ToolInput) -> Result<()> {
+        if input.primary.pressed {
+            sandbox.spawn_soft_body(ctx, input.cursor, self.settings)?;
+        }
+        Ok(())
+    }
+
+    fn draw_gizmo(
+        &mut self,
+        ctx: &Ctx,
+        _sandbox: &Sandbox,
+        gizmos: &Gizmos,
+        input: &ToolInput,
+    ) -> Result<()> {
+        match self.settings.shape {
+            SoftShape::Grid => gizmos.rect(
+                ctx,
+                (input.cursor, Vec2::splat(self.settings.size), 0.0).into(),
+            ),
+            SoftShape::Ring => gizmos.ring(ctx, input.cursor, self.settings.size),
+        }
+    }
+
+    fn ui(&mut self, ui: &mut egui::Ui, _sandbox: &Sandbox) {
+        let settings = &mut self.settings;
+        ui.horizontal(|ui| {
+            ui.radio_value(&mut settings.shape, SoftShape::Grid, "Grid");
+            ui.radio_value(&mut settings.shape, SoftShape::Ring, "Ring");
+            let range = match settings.shape {
+                SoftShape::Grid => 2..=12,
+                SoftShape::Ring => 3..=32,
+            };
+            ui.add(egui::Slider::new(&mut settings.resolution, range).text("Resolution"));
+            ui.add(egui::Slider::new(&mut settings.size, 0.03..=1.0).text("Size"));
+            ui.add(
+                egui::Slider::new(&mut settings.stiffness, 0.01..=20.0)
+                    .logarithmic(true)
+                    .text("Stiffness"),
+            );
+            ui.add(
+                egui::Slider::new(&mut settings.damping, 0.0..=0.1)
+                    .max_decimals(4)
+                    .text("Damping"),
+            );
+        });
+    }
+}
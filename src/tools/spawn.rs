@@ -0,0 +1,70 @@
+use let_engine::prelude::*;
+
+use super::{Gizmos, Tool, ToolInput};
+use crate::{
+    Ctx,
+    error::Result,
+    groups,
+    sandbox::{BodySettings, Sandbox},
+};
+
+/// Spawns boxes at the cursor. The secondary button removes objects like the delete tool.
+pub struct SpawnTool {
+    transform: Transform,
+    rotation: f32,
+    body: BodySettings,
+}
+
+impl SpawnTool {
+    pub fn new() -> Self {
+        Self {
+            transform: (vec2(0.0, 0.0), vec2(0.07, 0.07), 0.0).into(),
+            rotation: 0.0,
+            body: BodySettings::default(),
+        }
+    }
+}
+
+impl Tool for SpawnTool {
+    fn name(&self) -> &'static str {
+        "Spawn"
+    }
+
+    fn update(&mut self, ctx: &Ctx, sandbox: &mut Sandbox, input: &ToolInput) -> Result<()> {
+        self.transform.position = input.cursor;
+        if input.primary.pressed {
+            sandbox.spawn_square(ctx, self.transform, self.body)?;
+        }
+        if input.secondary.pressed {
+            for id in sandbox.objects_at(ctx, input.cursor) {
+                sandbox.remove_object(ctx, id);
+            }
+        }
+        Ok(())
+    }
+
+    fn draw_gizmo(
+        &mut self,
+        ctx: &Ctx,
+        _sandbox: &Sandbox,
+        gizmos: &Gizmos,
+        _input: &ToolInput,
+    ) -> Result<()> {
+        gizmos.rect(ctx, self.transform)
+    }
+
+    fn ui(&mut self, ui: &mut egui::Ui, sandbox: &Sandbox) {
+        ui.horizontal(|ui| {
+            ui.checkbox(&mut self.body.fixed, "Anchored");
+            ui.checkbox(&mut self.body.sensor, "Sensor");
+            groups::picker(ui, "spawn_group", &sandbox.groups, &mut self.body.group);
+            sandbox
+                .materials
+                .picker(ui, "spawn_material", &mut self.body.material);
+            ui.add(egui::Slider::new(&mut self.transform.size.x, 0.01..=1.0).text("Size X"));
+            ui.add(egui::Slider::new(&mut self.transform.size.y, 0.01..=1.0).text("Size Y"));
+            ui.add(egui::Slider::new(&mut self.rotation, 0.0..=90.0).text("Rotation"));
+            self.transform.rotation = self.rotation.to_radians();
+        });
+    }
+}
@@ -0,0 +1,142 @@
+use let_engine::prelude::*;
+
+use super::{Gizmos, Tool, ToolInput};
+use crate::{
+    Ctx,
+    error::Result,
+    motors::Motor,
+    sandbox::{BodySettings, BreakLimits, JointKind, Sandbox},
+};
+
+/// Which wheels the motors drive.
+#[derive(Clone, Copy, PartialEq)]
+enum Drive {
+    Front,
+    Rear,
+    All,
+}
+
+/// Spawns a chassis on two sprung, motorized wheels, driven with the arrow keys.
+pub struct VehicleTool {
+    /// Half extents of the chassis.
+    chassis: Vec2,
+    wheel_radius: f32,
+    stiffness: f32,
+    damping: f32,
+    travel: f32,
+    drive: Drive,
+    motor: Motor,
+}
+
+impl VehicleTool {
+    pub fn new() -> Self {
+        Self {
+            chassis: vec2(0.2, 0.04),
+            wheel_radius: 0.06,
+            stiffness: 2.0,
+            damping: 0.1,
+            travel: 0.03,
+            drive: Drive::All,
+            motor: Motor {
+                forward: Some("ArrowRight".to_string()),
+                backward: Some("ArrowLeft".to_string()),
+                ..Motor::default()
+            },
+        }
+    }
+
+    /// Wheel anchors on the chassis, rear (left) first.
+    fn axles(&self) -> [Vec2; 2] {
+        let y = self.chassis.y + self.wheel_radius * 0.5;
+        [
+            vec2(-self.chassis.x * 0.75, y),
+            vec2(self.chassis.x * 0.75, y),
+        ]
+    }
+
+    fn build(&self, ctx: &Ctx, sandbox: &mut Sandbox, position: Vec2) -> Result<()> {
+        let chassis = sandbox.spawn_square(
+            ctx,
+            (position, self.chassis, 0.0).into(),
+            BodySettings::default(),
+        )?;
+        let kind = JointKind::Wheel {
+            stiffness: self.stiffness,
+            damping: self.damping,
+            travel: self.travel,
+        };
+        for (index, axle) in self.axles().into_iter().enumerate() {
+            let wheel = sandbox.spawn_circle(
+                ctx,
+                (position + axle, Vec2::splat(self.wheel_radius), 0.0).into(),
+                BodySettings::default(),
+            )?;
+            sandbox.add_joint(
+                ctx,
+                (chassis, wheel),
+                kind,
+                (axle, Vec2::ZERO),
+                BreakLimits::default(),
+            )?;
+            let driven = match self.drive {
+                Drive::Front => index == 1,
+                Drive::Rear => index == 0,
+                Drive::All => true,
+            };
+            if driven && let Some(joint) = sandbox.joints.last_mut() {
+                joint.motor = Some(self.motor.clone());
+            }
+        }
+        sandbox.selected_object = Some(chassis);
+        Ok(())
+    }
+}
+
+impl Tool for VehicleTool {
+    fn name(&self) -> &'static str {
+        "Vehicle"
+    }
+
+    fn update(&mut self, ctx: &Ctx, sandbox: &mut Sandbox, input: &ToolInput) -> Result<()> {
+        if input.primary.pressed {
+            self.build(ctx, sandbox, input.cursor)?;
+        }
+        Ok(())
+    }
+
+    fn draw_gizmo(
+        &mut self,
+        ctx: &Ctx,
+        _sandbox: &Sandbox,
+        gizmos: &Gizmos,
+        input: &ToolInput,
+    ) -> Result<()> {
+        gizmos.rect(ctx, (input.cursor, self.chassis, 0.0).into())
+    }
+
+    fn ui(&mut self, ui: &mut egui::Ui, _sandbox: &Sandbox) {
+        ui.horizontal(|ui| {
+            ui.add(egui::Slider::new(&mut self.chassis.x, 0.05..=1.0).text("Length"));
+            ui.add(egui::Slider::new(&mut self.chassis.y, 0.01..=0.2).text("Height"));
+            ui.add(egui::Slider::new(&mut self.wheel_radius, 0.02..=0.3).text("Wheels"));
+            ui.add(
+                egui::Slider::new(&mut self.stiffness, 0.01..=50.0)
+                    .logarithmic(true)
+                    .text("Suspension"),
+            );
+            ui.add(egui::Slider::new(&mut self.damping, 0.0..=1.0).text("Damping"));
+            ui.add(egui::Slider::new(&mut self.travel, 0.0..=0.2).text("Travel"));
+        });
+        ui.horizontal(|ui| {
+            ui.radio_value(&mut self.drive, Drive::Rear, "Rear drive");
+            ui.radio_value(&mut self.drive, Drive::Front, "Front drive");
+            ui.radio_value(&mut self.drive, Drive::All, "All wheel drive");
+            ui.add(egui::Slider::new(&mut self.motor.speed, 0.0..=100.0).text("Speed"));
+            ui.add(
+                egui::Slider::new(&mut self.motor.max_torque, 0.001..=1.0)
+                    .logarithmic(true)
+                    .text("Torque"),
+            );
+        });
+    }
+}